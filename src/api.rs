@@ -1,17 +1,25 @@
 use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
 use reqwest::{
-    header::{HeaderMap, HeaderValue},
-    Client,
+    header::{HeaderMap, HeaderValue, CONTENT_TYPE},
+    Client, Method, Response, Url,
 };
-use serde::{de::Error, Serialize};
-use serde_json::Value;
+use serde::{de::DeserializeOwned, Serialize};
 use std::collections::BTreeMap;
+use std::time::Instant;
 
 use crate::{
-    helpers::{create_signature, get_prehash, get_timestamp},
+    clock::ServerClock,
+    credentials::Credentials,
+    error::{ApiError, ApiErrorKind, BatchOrderFailure, FoxbitError},
+    helpers::{create_signature, get_local_millis, get_prehash},
     types::{
-        Bank, CancelOrderResponse, Candlestick, CreateOrderResponse, Currency, CurrentTime,
-        FoxBitResponse, Market, MemberDetails, Order, OrderBook, Quote, Trade,
+        Account, Bank, BatchOrderRequest, BatchOrderResponse, BatchOrderResult, CancelMode,
+        CancelOrderResponse, CancelReplaceRequest, CancelReplaceResponse, CandleInterval,
+        Candlestick, CreateOrderResponse, Currency, CurrentTime, Deposit, DepositAddress,
+        DepositFilter, FoxBitResponse, Market, MemberDetails, Order, OrderBook, OrderFilter,
+        OrderRef, OrderRequest, OrderState, OtcExecution, OtcExecutionFilter, OtcMarket, OtcQuote,
+        OtcQuoteRequest, PublicTrade, Quote, Side, Ticker, Trade, TradeFilter, TradingFee,
+        TransactionalLimit, Withdrawal, WithdrawalFilter, WithdrawalRequest,
     },
 };
 
@@ -32,75 +40,93 @@ const QUERY_ENCODE_SET: &AsciiSet = &CONTROLS
     .add(b'|')
     .add(b'}')
     .add(b'+')
+    .add(b'&')
+    .add(b'=')
     .add(b'%'); // Add '%' for completeness, depending on your needs
 
 pub struct Api<'a> {
     client: &'a Client,
     base_url: &'a String,
-    api_secret: String,
-    access_key: String,
+    credentials: Option<&'a Credentials>,
+    clock: &'a ServerClock,
 }
 
 impl Api<'_> {
     pub fn new<'a>(
         client: &'a Client,
         base_url: &'a String,
-        api_secret: String,
-        access_key: String,
+        credentials: Option<&'a Credentials>,
+        clock: &'a ServerClock,
     ) -> Api<'a> {
         Api {
             client,
             base_url,
-            api_secret,
-            access_key,
+            credentials,
+            clock,
         }
     }
 
-    pub async fn list_currencies(&self) -> Result<Vec<Currency>, serde_json::Error> {
-        let endpoint = "/currencies";
-        let url = format!("{}{}", &self.base_url, endpoint);
-        let headers = self.get_headers(endpoint, None, None);
-        let response = self.send_get_request(&url, headers, None).await;
-
-        let json_response = serde_json::from_str::<FoxBitResponse<Vec<Currency>>>(&response);
-        match json_response {
-            Ok(json) => Ok(json.data),
-            Err(e) => {
-                eprintln!("Conversion to json failed: {}", e);
-                Err(e)
-            }
+    /// Measures the offset between the local clock and the server clock.
+    pub async fn sync_clock(&self) -> Result<(), FoxbitError> {
+        let _sync = self.clock.lock_sync().await;
+        self.measure_clock().await
+    }
+
+    /// Measures the clock offset when a periodic sync is due, unless another
+    /// request did so while this one was waiting for the lock.
+    pub(crate) async fn sync_clock_if_due(&self) -> Result<(), FoxbitError> {
+        if !self.clock.needs_sync() {
+            return Ok(());
         }
+        let _sync = self.clock.lock_sync().await;
+        if !self.clock.needs_sync() {
+            return Ok(());
+        }
+        self.measure_clock().await
+    }
+
+    async fn measure_clock(&self) -> Result<(), FoxbitError> {
+        let sent_at = get_local_millis();
+        let started = Instant::now();
+        let current_time = self.get_current_time().await.inspect_err(|_| {
+            self.clock.record_failure();
+        })?;
+        self.clock
+            .record(sent_at, started.elapsed(), current_time.timestamp);
+        Ok(())
+    }
+
+    pub async fn list_currencies(&self) -> Result<Vec<Currency>, FoxbitError> {
+        let endpoint = "/currencies";
+        let response = self.send_public_request(endpoint, None).await?;
+
+        Self::decode::<FoxBitResponse<Vec<Currency>>>(response).map(|json| json.data)
     }
 
-    pub async fn list_markets(&self) -> Result<Vec<Market>, serde_json::Error> {
+    pub async fn list_markets(&self) -> Result<Vec<Market>, FoxbitError> {
         let endpoint = "/markets";
-        let url = format!("{}{}", &self.base_url, endpoint);
-        let headers = self.get_headers(endpoint, None, None);
-        let response = self.send_get_request(&url, headers, None).await;
-
-        let json_response = serde_json::from_str::<FoxBitResponse<Vec<Market>>>(&response);
-        match json_response {
-            Ok(json) => Ok(json.data),
-            Err(e) => {
-                eprintln!("Conversion to json failed: {}", e);
-                Err(e)
-            }
-        }
+        let response = self.send_public_request(endpoint, None).await?;
+
+        Self::decode::<FoxBitResponse<Vec<Market>>>(response).map(|json| json.data)
     }
 
     pub async fn get_market_quotation(
         &self,
-        side: &str,
+        side: Side,
         base_currency: &str,
         quote_currency: &str,
         quantity: Option<&str>,
         amount: Option<&str>,
-    ) -> Result<Quote, serde_json::Error> {
+    ) -> Result<Quote, FoxbitError> {
         if quantity.is_none() && amount.is_none() {
-            return Err(serde_json::Error::custom("Must receive quantity or amount"));
+            return Err(FoxbitError::Validation(
+                "Must receive quantity or amount".into(),
+            ));
         }
+        // The quotes endpoint expects the side in lowercase.
+        let side = side.as_str().to_lowercase();
         let mut query_params: BTreeMap<&str, &str> = BTreeMap::new();
-        query_params.insert("side", side);
+        query_params.insert("side", &side);
         query_params.insert("base_currency", base_currency);
         query_params.insert("quote_currency", quote_currency);
 
@@ -111,166 +137,218 @@ impl Api<'_> {
             query_params.insert("amount", amt);
         }
 
-        let query_string = self.build_query_string(&query_params);
         let endpoint = "/markets/quotes";
-        let url = format!("{}{}", &self.base_url, endpoint);
-        let headers = self.get_headers(endpoint, Some(query_string), None);
         let response = self
-            .send_get_request(&url, headers, Some(&query_params))
-            .await;
-
-        let json_response = serde_json::from_str::<Quote>(&response);
-        match json_response {
-            Ok(json) => Ok(json),
-            Err(e) => {
-                eprintln!("Conversion to json failed: {}", e);
-                Err(e)
-            }
-        }
+            .send_public_request(endpoint, Some(&query_params))
+            .await?;
+
+        Self::decode::<Quote>(response)
     }
 
     pub async fn get_order_book(
         &self,
         market_symbol: &str,
         depth: u8,
-    ) -> Result<OrderBook, serde_json::Error> {
+    ) -> Result<OrderBook, FoxbitError> {
         let depth_str = format!("{}", depth);
         let mut query_params: BTreeMap<&str, &str> = BTreeMap::new();
         query_params.insert("depth", depth_str.as_str());
-        let query_string = self.build_query_string(&query_params);
 
         let endpoint = format!("/markets/{}/orderbook", market_symbol);
-        let url = format!("{}{}", &self.base_url, endpoint);
-        let headers = self.get_headers(&endpoint, Some(query_string), None);
         let response = self
-            .send_get_request(&url, headers, Some(&query_params))
-            .await;
-        println!("{:?}", response);
-        let json_response = serde_json::from_str::<OrderBook>(&response);
-        match json_response {
-            Ok(json) => Ok(json),
-            Err(e) => {
-                eprintln!("Conversion to json failed: {}", e);
-                Err(e)
-            }
-        }
+            .send_public_request(&endpoint, Some(&query_params))
+            .await?;
+        Self::decode::<OrderBook>(response)
     }
 
     pub async fn get_candlesticks(
         &self,
         market_symbol: &str,
-        interval: &str,
+        interval: CandleInterval,
         start_time: &str,
         end_time: &str,
-    ) -> Result<Vec<Candlestick>, serde_json::Error> {
+    ) -> Result<Vec<Candlestick>, FoxbitError> {
         let mut query_params: BTreeMap<&str, &str> = BTreeMap::new();
-        query_params.insert("interval", interval);
+        query_params.insert("interval", interval.as_str());
         query_params.insert("start_time", start_time);
         query_params.insert("end_time", end_time);
-        let query_string = self.build_query_string(&query_params);
 
         let endpoint = format!("/markets/{}/candlesticks", market_symbol);
-        let url = format!("{}{}", &self.base_url, endpoint);
-        let headers = self.get_headers(&endpoint, Some(query_string), None);
         let response = self
-            .send_get_request(&url, headers, Some(&query_params))
-            .await;
-
-        let json_response = serde_json::from_str::<Vec<Candlestick>>(&response);
-        match json_response {
-            Ok(json) => Ok(json),
-            Err(e) => {
-                eprintln!("Conversion to json failed: {}", e);
-                Err(e)
-            }
+            .send_public_request(&endpoint, Some(&query_params))
+            .await?;
+
+        Self::decode::<Vec<Candlestick>>(response)
+    }
+
+    pub async fn get_ticker(&self, market_symbol: &str) -> Result<Ticker, FoxbitError> {
+        let endpoint = format!("/markets/{}/ticker/24hr", market_symbol);
+        let response = self.send_public_request(&endpoint, None).await?;
+
+        Self::decode::<FoxBitResponse<[Ticker; 1]>>(response).map(|json| {
+            let [ticker] = json.data;
+            ticker
+        })
+    }
+
+    pub async fn list_tickers(&self) -> Result<Vec<Ticker>, FoxbitError> {
+        let endpoint = "/markets/ticker/24hr";
+        let response = self.send_public_request(endpoint, None).await?;
+
+        Self::decode::<FoxBitResponse<Vec<Ticker>>>(response).map(|json| json.data)
+    }
+
+    pub async fn get_market_trades(
+        &self,
+        market_symbol: &str,
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+        page_size: Option<usize>,
+        page: Option<usize>,
+    ) -> Result<Vec<PublicTrade>, FoxbitError> {
+        let ps = page_size.map(|ps| ps.to_string());
+        let pg = page.map(|pg| pg.to_string());
+        let mut query_params: BTreeMap<&str, &str> = BTreeMap::new();
+        if let Some(start_time) = start_time {
+            query_params.insert("start_time", start_time);
         }
+        if let Some(end_time) = end_time {
+            query_params.insert("end_time", end_time);
+        }
+        if let Some(ps) = &ps {
+            query_params.insert("page_size", ps);
+        }
+        if let Some(pg) = &pg {
+            query_params.insert("page", pg);
+        }
+
+        let endpoint = format!("/markets/{}/trades/history", market_symbol);
+        let response = self
+            .send_public_request(&endpoint, Some(&query_params))
+            .await?;
+
+        Self::decode::<FoxBitResponse<Vec<PublicTrade>>>(response).map(|json| json.data)
     }
 
-    pub async fn list_banks(&self) -> Result<Vec<Bank>, serde_json::Error> {
+    pub async fn list_banks(&self) -> Result<Vec<Bank>, FoxbitError> {
         let endpoint = "/banks".to_string();
-        let url = format!("{}{}", &self.base_url, endpoint);
-        let headers = self.get_headers(&endpoint, None, None);
-        let response = self.send_get_request(&url, headers, None).await;
-
-        let json_response = serde_json::from_str::<FoxBitResponse<Vec<Bank>>>(&response);
-        match json_response {
-            Ok(json) => Ok(json.data),
-            Err(e) => {
-                eprintln!("Conversion to json failed: {}", e);
-                Err(e)
-            }
-        }
+        let response = self.send_public_request(&endpoint, None).await?;
+
+        Self::decode::<FoxBitResponse<Vec<Bank>>>(response).map(|json| json.data)
     }
 
-    pub async fn get_current_time(&self) -> Result<CurrentTime, serde_json::Error> {
+    pub async fn get_current_time(&self) -> Result<CurrentTime, FoxbitError> {
         let endpoint = "/system/time".to_string();
-        let url = format!("{}{}", &self.base_url, endpoint);
-        let headers = self.get_headers(&endpoint, None, None);
-        let response = self.send_get_request(&url, headers, None).await;
-
-        let json_response = serde_json::from_str::<CurrentTime>(&response);
-        match json_response {
-            Ok(json) => Ok(json),
-            Err(e) => {
-                eprintln!("Conversion to json failed: {}", e);
-                Err(e)
-            }
-        }
+        let response = self.send_public_request(&endpoint, None).await?;
+
+        Self::decode::<CurrentTime>(response)
     }
 
-    pub async fn get_current_member_details(&self) -> Result<MemberDetails, serde_json::Error> {
+    pub async fn get_current_member_details(&self) -> Result<MemberDetails, FoxbitError> {
         let endpoint = "/me".to_string();
-        let url = format!("{}{}", &self.base_url, endpoint);
-        let headers = self.get_headers(&endpoint, None, None);
-        let response = self.send_get_request(&url, headers, None).await;
-
-        println!("{:?}", response);
-        let json_response = serde_json::from_str::<MemberDetails>(&response);
-        match json_response {
-            Ok(json) => Ok(json),
-            Err(e) => {
-                eprintln!("Conversion to json failed: {}", e);
-                Err(e)
-            }
-        }
+        let response = self
+            .send_signed_request(Method::GET, &endpoint, None, None)
+            .await?;
+
+        Self::decode::<MemberDetails>(response)
     }
 
-    pub async fn create_order(
+    pub async fn get_trading_fees(&self) -> Result<Vec<TradingFee>, FoxbitError> {
+        let endpoint = "/me/fees/trading".to_string();
+        let response = self
+            .send_signed_request(Method::GET, &endpoint, None, None)
+            .await?;
+
+        Self::decode::<FoxBitResponse<Vec<TradingFee>>>(response).map(|json| json.data)
+    }
+
+    pub async fn list_transactional_limits(&self) -> Result<Vec<TransactionalLimit>, FoxbitError> {
+        let endpoint = "/me/limits".to_string();
+        let response = self
+            .send_signed_request(Method::GET, &endpoint, None, None)
+            .await?;
+
+        Self::decode::<FoxBitResponse<Vec<TransactionalLimit>>>(response).map(|json| json.data)
+    }
+
+    pub async fn submit_order(
         &self,
-        side: &str,
-        r#type: &str,
-        market_symbol: &str,
-        quantity: &str,
-        client_order_id: Option<&str>,
-        remark: Option<&str>,
-    ) -> Result<CreateOrderResponse, serde_json::Error> {
+        order: &OrderRequest,
+    ) -> Result<CreateOrderResponse, FoxbitError> {
         let endpoint = "/orders".to_string();
-        let url = format!("{}{}", &self.base_url, endpoint);
-        let body = serde_json::json!({
-            "side": side,
-            "type": r#type,
-            "market_symbol": market_symbol,
-            "quantity": quantity,
-            "client_order_id": client_order_id.unwrap(),
-            "remark": remark.unwrap(),
-        });
-        let headers = self.get_headers(&endpoint, None, Some(&body));
-
-        let response = self.send_post_request(&url, headers, &body).await.unwrap_or_else(|e| {
-            eprintln!("Request to Foxbit failed: {}", e);
-            e.to_string()
-        });
-
-        let json_response = serde_json::from_str::<CreateOrderResponse>(&response);
-        match json_response {
-            Ok(json) => Ok(json),
-            Err(e) => {
-                eprintln!("Conversion to json failed: {}", e);
-                Err(e)
-            }
-        }
+        let body = Self::encode_body(order)?;
+
+        let response = self
+            .send_signed_request(Method::POST, &endpoint, None, Some(body))
+            .await?;
+
+        Self::decode::<CreateOrderResponse>(response)
     }
 
+    /// Submits one batch of orders. The results are returned in the order of
+    /// the requests. Rejected orders carry the status of the batch response,
+    /// as Foxbit reports no status per order.
+    pub async fn create_orders_batch(
+        &self,
+        orders: &[OrderRequest],
+    ) -> Result<Vec<BatchOrderResult>, FoxbitError> {
+        let endpoint = "/orders/batch".to_string();
+        let body = BatchOrderRequest {
+            validate_only: false,
+            requests: orders,
+        };
+        let body = Self::encode_body(&body)?;
+
+        let (status, response) = self
+            .send_signed_request_with_status(Method::POST, &endpoint, None, Some(body))
+            .await?;
+
+        let results = serde_json::from_str::<FoxBitResponse<Vec<BatchOrderResponse>>>(&response)
+            .and_then(|json| {
+                if json.data.len() == orders.len() {
+                    Ok(json.data)
+                } else {
+                    Err(serde::de::Error::custom(format!(
+                        "expected {} order results, got {}",
+                        orders.len(),
+                        json.data.len()
+                    )))
+                }
+            })
+            .map_err(|source| FoxbitError::Decode {
+                source,
+                body: response,
+            })?;
+
+        Ok(orders
+            .iter()
+            .zip(results)
+            .map(|(order, result)| BatchOrderResult {
+                client_order_id: order.client_order_id.clone(),
+                result: match (result.error, result.id, result.sn) {
+                    (None, Some(id), Some(sn)) => Ok(CreateOrderResponse {
+                        id,
+                        sn,
+                        client_order_id: result.client_order_id.unwrap_or_default(),
+                    }),
+                    (Some(error), _, _) => Err(BatchOrderFailure::Rejected(ApiError::new(
+                        status,
+                        error.code,
+                        error.message,
+                        vec![],
+                    ))),
+                    (None, _, _) => Err(BatchOrderFailure::Rejected(ApiError::new(
+                        status,
+                        None,
+                        "order result has neither an id nor an error".into(),
+                        vec![],
+                    ))),
+                },
+            })
+            .collect())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn list_orders(
         &self,
         start_time: &str,
@@ -278,97 +356,109 @@ impl Api<'_> {
         page_size: usize,
         page: usize,
         market_symbol: &str,
-        state: &str,
-        side: &str,
-    ) -> Result<Vec<Order>, serde_json::Error> {
-        let ps = page_size.to_string();
-        let pg = page.to_string();
+        state: OrderState,
+        side: Side,
+    ) -> Result<Vec<Order>, FoxbitError> {
+        let filter = OrderFilter {
+            market_symbol: Some(market_symbol.to_string()),
+            state: Some(state),
+            side: Some(side),
+            start_time: Some(start_time.to_string()),
+            end_time: Some(end_time.to_string()),
+            page_size: Some(page_size),
+            page: Some(page),
+        };
+        self.list_orders_page(&filter).await
+    }
+
+    pub async fn list_orders_page(&self, filter: &OrderFilter) -> Result<Vec<Order>, FoxbitError> {
+        let ps = filter.page_size.map(|ps| ps.to_string());
+        let pg = filter.page.map(|pg| pg.to_string());
         let mut query_params: BTreeMap<&str, &str> = BTreeMap::new();
-        query_params.insert("start_time", start_time);
-        query_params.insert("end_time", end_time);
-        query_params.insert("page_size", &ps);
-        query_params.insert("page", &pg);
-        query_params.insert("market_symbol", market_symbol);
-        query_params.insert("state", state);
-        query_params.insert("side", side);
+        if let Some(market_symbol) = &filter.market_symbol {
+            query_params.insert("market_symbol", market_symbol);
+        }
+        if let Some(state) = &filter.state {
+            query_params.insert("state", state.as_str());
+        }
+        if let Some(side) = &filter.side {
+            query_params.insert("side", side.as_str());
+        }
+        if let Some(start_time) = &filter.start_time {
+            query_params.insert("start_time", start_time);
+        }
+        if let Some(end_time) = &filter.end_time {
+            query_params.insert("end_time", end_time);
+        }
+        if let Some(ps) = &ps {
+            query_params.insert("page_size", ps);
+        }
+        if let Some(pg) = &pg {
+            query_params.insert("page", pg);
+        }
         let endpoint = "/orders".to_string();
-        let query_string = self.build_query_string(&query_params);
-        let url = format!("{}{}", &self.base_url, endpoint);
-        let headers = self.get_headers(&endpoint, Some(query_string), None);
         let response = self
-            .send_get_request(&url, headers, Some(&query_params))
-            .await;
-
-        let json_response = serde_json::from_str::<FoxBitResponse<Vec<Order>>>(&response);
-        match json_response {
-            Ok(json) => Ok(json.data),
-            Err(e) => {
-                eprintln!("Conversion to json failed: {}", e);
-                Err(e)
-            }
-        }
+            .send_signed_request(Method::GET, &endpoint, Some(&query_params), None)
+            .await?;
+
+        Self::decode::<FoxBitResponse<Vec<Order>>>(response).map(|json| json.data)
     }
 
-    pub async fn get_order_by_id(&self, order_id: &str) -> Result<Order, serde_json::Error> {
+    pub async fn get_order_by_id(&self, order_id: &str) -> Result<Order, FoxbitError> {
         let endpoint = format!("/orders/by-order-id/{}", order_id);
-        let url = format!("{}{}", &self.base_url, endpoint);
-        let headers = self.get_headers(&endpoint, None, None);
-        let response = self.send_get_request(&url, headers, None).await;
-
-        let json_response = serde_json::from_str::<Order>(&response);
-        match json_response {
-            Ok(json) => Ok(json),
-            Err(e) => {
-                eprintln!("Conversion to json failed: {}", e);
-                Err(e)
-            }
-        }
+        let response = self
+            .send_signed_request(Method::GET, &endpoint, None, None)
+            .await?;
+
+        Self::decode::<Order>(response)
     }
 
     pub async fn get_order_by_client_id(
         &self,
         client_order_id: &str,
-    ) -> Result<Order, serde_json::Error> {
+    ) -> Result<Order, FoxbitError> {
         let endpoint = format!("/orders/by-client-order-id/{}", client_order_id);
-        let url = format!("{}{}", &self.base_url, endpoint);
-        let headers = self.get_headers(&endpoint, None, None);
-        let response = self.send_get_request(&url, headers, None).await;
-
-        let json_response = serde_json::from_str::<Order>(&response);
-        match json_response {
-            Ok(json) => Ok(json),
-            Err(e) => {
-                eprintln!("Conversion to json failed: {}", e);
-                Err(e)
-            }
-        }
+        let response = self
+            .send_signed_request(Method::GET, &endpoint, None, None)
+            .await?;
+
+        Self::decode::<Order>(response)
     }
 
     pub async fn cancel_orders(
         &self,
-        r#type: &str,
-    ) -> Result<Vec<CancelOrderResponse>, serde_json::Error> {
+        mode: &CancelMode,
+    ) -> Result<Vec<CancelOrderResponse>, FoxbitError> {
         let endpoint = "/orders/cancel".to_string();
-        let url = format!("{}{}", &self.base_url, endpoint);
-        let body = serde_json::json!({
-            "type": r#type,
-        });
-        let headers = self.get_headers(&endpoint, None, Some(&body));
-
-        let response = self.send_put_request(&url, headers, &body).await.unwrap_or_else(|e| {
-            eprintln!("Request to Foxbit failed: {}", e);
-            e.to_string()
-        });
-        
-        let json_response =
-            serde_json::from_str::<FoxBitResponse<Vec<CancelOrderResponse>>>(&response);
-        match json_response {
-            Ok(json) => Ok(json.data),
-            Err(e) => {
-                eprintln!("Conversion to json failed: {}", e);
-                Err(e)
-            }
-        }
+        let body = Self::encode_body(mode)?;
+
+        let response = self
+            .send_signed_request(Method::PUT, &endpoint, None, Some(body))
+            .await?;
+
+        Self::decode::<FoxBitResponse<Vec<CancelOrderResponse>>>(response).map(|json| json.data)
+    }
+
+    /// Cancels an order and creates its replacement in a single request. The
+    /// replacement is only created if the cancellation succeeds.
+    pub async fn cancel_and_replace(
+        &self,
+        cancel: &OrderRef,
+        create: &OrderRequest,
+    ) -> Result<CancelReplaceResponse, FoxbitError> {
+        let endpoint = "/orders/cancel-replace".to_string();
+        let body = CancelReplaceRequest {
+            mode: "STRICT",
+            cancel,
+            create,
+        };
+        let body = Self::encode_body(&body)?;
+
+        let response = self
+            .send_signed_request(Method::POST, &endpoint, None, Some(body))
+            .await?;
+
+        Self::decode::<CancelReplaceResponse>(response)
     }
 
     pub async fn list_trades(
@@ -378,128 +468,393 @@ impl Api<'_> {
         page_size: usize,
         page: usize,
         market_symbol: &str,
-    ) -> Result<Vec<Trade>, serde_json::Error> {
-        let ps = page_size.to_string();
-        let pg = page.to_string();
+    ) -> Result<Vec<Trade>, FoxbitError> {
+        let filter = TradeFilter {
+            market_symbol: Some(market_symbol.to_string()),
+            start_time: Some(start_time.to_string()),
+            end_time: Some(end_time.to_string()),
+            page_size: Some(page_size),
+            page: Some(page),
+        };
+        self.list_trades_page(&filter).await
+    }
+
+    pub async fn list_trades_page(&self, filter: &TradeFilter) -> Result<Vec<Trade>, FoxbitError> {
+        let ps = filter.page_size.map(|ps| ps.to_string());
+        let pg = filter.page.map(|pg| pg.to_string());
         let mut query_params: BTreeMap<&str, &str> = BTreeMap::new();
-        query_params.insert("start_time", start_time);
-        query_params.insert("end_time", end_time);
-        query_params.insert("page_size", &ps);
-        query_params.insert("page", &pg);
-        query_params.insert("market_symbol", &market_symbol);
+        if let Some(market_symbol) = &filter.market_symbol {
+            query_params.insert("market_symbol", market_symbol);
+        }
+        if let Some(start_time) = &filter.start_time {
+            query_params.insert("start_time", start_time);
+        }
+        if let Some(end_time) = &filter.end_time {
+            query_params.insert("end_time", end_time);
+        }
+        if let Some(ps) = &ps {
+            query_params.insert("page_size", ps);
+        }
+        if let Some(pg) = &pg {
+            query_params.insert("page", pg);
+        }
         let endpoint = "/trades".to_string();
-        let query_string = self.build_query_string(&query_params);
-        let url = format!("{}{}", &self.base_url, endpoint);
-        let headers = self.get_headers(&endpoint, Some(query_string), None);
         let response = self
-            .send_get_request(&url, headers, Some(&query_params))
-            .await;
-
-        let json_response = serde_json::from_str::<FoxBitResponse<Vec<Trade>>>(&response);
-        match json_response {
-            Ok(json) => Ok(json.data),
-            Err(e) => {
-                eprintln!("Conversion to json failed: {}", e);
-                Err(e)
-            }
+            .send_signed_request(Method::GET, &endpoint, Some(&query_params), None)
+            .await?;
+
+        Self::decode::<FoxBitResponse<Vec<Trade>>>(response).map(|json| json.data)
+    }
+
+    pub async fn list_accounts(&self) -> Result<Vec<Account>, FoxbitError> {
+        let endpoint = "/accounts".to_string();
+        let response = self
+            .send_signed_request(Method::GET, &endpoint, None, None)
+            .await?;
+
+        Self::decode::<FoxBitResponse<Vec<Account>>>(response).map(|json| json.data)
+    }
+
+    pub async fn list_deposits(&self, filter: &DepositFilter) -> Result<Vec<Deposit>, FoxbitError> {
+        let ps = filter.page_size.map(|ps| ps.to_string());
+        let pg = filter.page.map(|pg| pg.to_string());
+        let mut query_params: BTreeMap<&str, &str> = BTreeMap::new();
+        if let Some(state) = &filter.state {
+            query_params.insert("state", state);
+        }
+        if let Some(currency_symbol) = &filter.currency_symbol {
+            query_params.insert("currency_symbol", currency_symbol);
+        }
+        if let Some(start_time) = &filter.start_time {
+            query_params.insert("start_time", start_time);
+        }
+        if let Some(end_time) = &filter.end_time {
+            query_params.insert("end_time", end_time);
+        }
+        if let Some(ps) = &ps {
+            query_params.insert("page_size", ps);
+        }
+        if let Some(pg) = &pg {
+            query_params.insert("page", pg);
+        }
+        let endpoint = "/deposits".to_string();
+        let response = self
+            .send_signed_request(Method::GET, &endpoint, Some(&query_params), None)
+            .await?;
+
+        Self::decode::<FoxBitResponse<Vec<Deposit>>>(response).map(|json| json.data)
+    }
+
+    pub async fn get_deposit(&self, sn: &str) -> Result<Deposit, FoxbitError> {
+        let endpoint = format!("/deposits/{}", sn);
+        let response = self
+            .send_signed_request(Method::GET, &endpoint, None, None)
+            .await?;
+
+        Self::decode::<Deposit>(response)
+    }
+
+    pub async fn get_deposit_address(
+        &self,
+        currency_symbol: &str,
+        network_code: Option<&str>,
+    ) -> Result<DepositAddress, FoxbitError> {
+        let mut query_params: BTreeMap<&str, &str> = BTreeMap::new();
+        query_params.insert("currency_symbol", currency_symbol);
+        if let Some(network_code) = network_code {
+            query_params.insert("network_code", network_code);
         }
+        let endpoint = "/deposits/address".to_string();
+        let response = self
+            .send_signed_request(Method::GET, &endpoint, Some(&query_params), None)
+            .await?;
+
+        Self::decode::<DepositAddress>(response)
     }
 
+    pub async fn list_withdrawals(
+        &self,
+        filter: &WithdrawalFilter,
+    ) -> Result<Vec<Withdrawal>, FoxbitError> {
+        let ps = filter.page_size.map(|ps| ps.to_string());
+        let pg = filter.page.map(|pg| pg.to_string());
+        let mut query_params: BTreeMap<&str, &str> = BTreeMap::new();
+        if let Some(state) = &filter.state {
+            query_params.insert("state", state);
+        }
+        if let Some(currency_symbol) = &filter.currency_symbol {
+            query_params.insert("currency_symbol", currency_symbol);
+        }
+        if let Some(start_time) = &filter.start_time {
+            query_params.insert("start_time", start_time);
+        }
+        if let Some(end_time) = &filter.end_time {
+            query_params.insert("end_time", end_time);
+        }
+        if let Some(ps) = &ps {
+            query_params.insert("page_size", ps);
+        }
+        if let Some(pg) = &pg {
+            query_params.insert("page", pg);
+        }
+        let endpoint = "/withdrawals".to_string();
+        let response = self
+            .send_signed_request(Method::GET, &endpoint, Some(&query_params), None)
+            .await?;
+
+        Self::decode::<FoxBitResponse<Vec<Withdrawal>>>(response).map(|json| json.data)
+    }
+
+    pub async fn create_withdrawal(
+        &self,
+        request: &WithdrawalRequest,
+    ) -> Result<Withdrawal, FoxbitError> {
+        let endpoint = "/withdrawals".to_string();
+        let body = Self::encode_body(request)?;
+        let response = self
+            .send_signed_request(Method::POST, &endpoint, None, Some(body))
+            .await?;
+
+        Self::decode::<Withdrawal>(response)
+    }
+
+    pub async fn get_withdrawal(&self, sn: &str) -> Result<Withdrawal, FoxbitError> {
+        let endpoint = format!("/withdrawals/{}", sn);
+        let response = self
+            .send_signed_request(Method::GET, &endpoint, None, None)
+            .await?;
+
+        Self::decode::<Withdrawal>(response)
+    }
+
+    pub async fn list_otc_markets(&self) -> Result<Vec<OtcMarket>, FoxbitError> {
+        let endpoint = "/otc/markets".to_string();
+        let response = self
+            .send_signed_request(Method::GET, &endpoint, None, None)
+            .await?;
+
+        Self::decode::<FoxBitResponse<Vec<OtcMarket>>>(response).map(|json| json.data)
+    }
+
+    pub async fn request_otc_quote(
+        &self,
+        request: &OtcQuoteRequest,
+    ) -> Result<OtcQuote, FoxbitError> {
+        if request.quantity.is_none() && request.amount.is_none() {
+            return Err(FoxbitError::Validation(
+                "Must receive quantity or amount".into(),
+            ));
+        }
+        let endpoint = "/otc/quotes".to_string();
+        let body = Self::encode_body(request)?;
+        let response = self
+            .send_signed_request(Method::POST, &endpoint, None, Some(body))
+            .await?;
+
+        Self::decode::<OtcQuote>(response)
+    }
+
+    pub async fn execute_otc_quote(&self, quote_id: &str) -> Result<OtcExecution, FoxbitError> {
+        let endpoint = format!("/otc/quotes/{}/execute", quote_id);
+        let response = self
+            .send_signed_request(Method::POST, &endpoint, None, None)
+            .await?;
+
+        Self::decode::<OtcExecution>(response)
+    }
+
+    pub async fn list_otc_executions(
+        &self,
+        filter: &OtcExecutionFilter,
+    ) -> Result<Vec<OtcExecution>, FoxbitError> {
+        let ps = filter.page_size.map(|ps| ps.to_string());
+        let pg = filter.page.map(|pg| pg.to_string());
+        let mut query_params: BTreeMap<&str, &str> = BTreeMap::new();
+        if let Some(market_symbol) = &filter.market_symbol {
+            query_params.insert("market_symbol", market_symbol);
+        }
+        if let Some(start_time) = &filter.start_time {
+            query_params.insert("start_time", start_time);
+        }
+        if let Some(end_time) = &filter.end_time {
+            query_params.insert("end_time", end_time);
+        }
+        if let Some(ps) = &ps {
+            query_params.insert("page_size", ps);
+        }
+        if let Some(pg) = &pg {
+            query_params.insert("page", pg);
+        }
+        let endpoint = "/otc/executions".to_string();
+        let response = self
+            .send_signed_request(Method::GET, &endpoint, Some(&query_params), None)
+            .await?;
+
+        Self::decode::<FoxBitResponse<Vec<OtcExecution>>>(response).map(|json| json.data)
+    }
+
+    /// Signs the path and query of `url` as they are sent, so that the
+    /// signature holds whatever base path the client is configured with.
     fn get_headers(
         &self,
-        endpoint: &str,
-        query_string: Option<String>,
-        body: Option<&Value>,
-    ) -> HeaderMap {
-        let timestamp = get_timestamp();
-        let prehash = get_prehash(endpoint, &timestamp, query_string.as_deref(), body);
-        let signature = create_signature(&prehash, &self.api_secret);
-        let mut headers = reqwest::header::HeaderMap::new();
+        method: &Method,
+        url: &Url,
+        body: &str,
+    ) -> Result<HeaderMap, FoxbitError> {
+        let credentials = self.credentials.ok_or_else(|| {
+            FoxbitError::Config(format!("{} requires an authenticated client", url.path()))
+        })?;
+        let timestamp = self.clock.now_millis().to_string();
+        let prehash = get_prehash(
+            method,
+            url.path(),
+            &timestamp,
+            url.query().unwrap_or_default(),
+            body,
+        );
+        let signature = create_signature(&prehash, credentials.api_secret());
+        let header_value = |name: &str, value: &str| {
+            HeaderValue::from_str(value).map_err(|_| {
+                FoxbitError::Config(format!(
+                    "{} contains characters not allowed in a header",
+                    name
+                ))
+            })
+        };
+        let mut headers = HeaderMap::new();
         headers.insert(
             "X-FB-ACCESS-KEY",
-            reqwest::header::HeaderValue::from_str(&self.access_key).unwrap(),
+            header_value("access key", credentials.access_key())?,
         );
         headers.insert(
             "X-FB-ACCESS-TIMESTAMP",
-            HeaderValue::from_str(&timestamp).unwrap(),
+            header_value("timestamp", &timestamp)?,
         );
         headers.insert(
             "X-FB-ACCESS-SIGNATURE",
-            HeaderValue::from_str(&signature).unwrap(),
+            header_value("signature", &signature)?,
         );
-        headers
+        Ok(headers)
     }
 
-    async fn send_get_request(
+    async fn send_public_request(
         &self,
-        url: &str,
-        headers: HeaderMap,
+        endpoint: &str,
         query_params: Option<&BTreeMap<&str, &str>>,
-    ) -> String {
-        let request_builder = self.client.get(url).headers(headers);
+    ) -> Result<String, FoxbitError> {
+        let query_string = self.build_query_string(query_params);
+        let url = self.build_url(endpoint, &query_string);
 
-        let request_builder = if let Some(params) = query_params {
-            request_builder.query(&params)
-        } else {
-            request_builder
-        };
-        
-        match request_builder.send().await {
-            Ok(resp) => match resp.text().await {
-                Ok(text_response) => {
-                    text_response
-                }
-                Err(e) => {
-                    eprintln!("Converting Foxbit response to text failed: {}", e);
-                    e.to_string()
+        let response = self.client.get(url).send().await?;
+
+        Self::read_response(response).await.map(|(_, body)| body)
+    }
+
+    /// Signs and sends a request. The query string and body are encoded once,
+    /// so the bytes covered by the signature are exactly the bytes sent.
+    ///
+    /// The server clock offset is refreshed when it is due, and once more if
+    /// Foxbit rejects the request timestamp, in which case the request is
+    /// signed again and resent. A scheduled refresh that fails does not fail
+    /// the request, which is then signed with the last known offset.
+    async fn send_signed_request(
+        &self,
+        method: Method,
+        endpoint: &str,
+        query_params: Option<&BTreeMap<&str, &str>>,
+        body: Option<String>,
+    ) -> Result<String, FoxbitError> {
+        self.send_signed_request_with_status(method, endpoint, query_params, body)
+            .await
+            .map(|(_, body)| body)
+    }
+
+    /// Like `send_signed_request`, but also returns the HTTP status of the
+    /// response.
+    async fn send_signed_request_with_status(
+        &self,
+        method: Method,
+        endpoint: &str,
+        query_params: Option<&BTreeMap<&str, &str>>,
+        body: Option<String>,
+    ) -> Result<(u16, String), FoxbitError> {
+        // Failures are covered by the resync on a rejected timestamp below,
+        // and are not retried before the clock's retry delay.
+        let _ = self.sync_clock_if_due().await;
+
+        let query_string = self.build_query_string(query_params);
+        let url = self.build_url(endpoint, &query_string);
+        let url = Url::parse(&url)
+            .map_err(|e| FoxbitError::Config(format!("invalid API URL {}: {}", url, e)))?;
+        let body = body.unwrap_or_default();
+        let mut resynced = false;
+
+        loop {
+            let headers = self.get_headers(&method, &url, &body)?;
+            let request_builder = self
+                .client
+                .request(method.clone(), url.clone())
+                .headers(headers);
+
+            let request_builder = if body.is_empty() {
+                request_builder
+            } else {
+                request_builder
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(body.clone())
+            };
+
+            let response = request_builder.send().await?;
+
+            match Self::read_response(response).await {
+                Err(FoxbitError::Api(e))
+                    if e.kind == ApiErrorKind::InvalidTimestamp && !resynced =>
+                {
+                    if self.sync_clock().await.is_err() {
+                        return Err(FoxbitError::Api(e));
+                    }
+                    resynced = true;
                 }
-            },
-            Err(e) => {
-                eprintln!("Request to Foxbit failed: {}", e);
-                e.to_string()
+                result => return result,
             }
         }
     }
 
-    async fn send_post_request<T: Serialize + ?Sized>(
-        &self,
-        url: &str,
-        headers: HeaderMap,
-        body: &T,
-    ) -> Result<String, reqwest::Error> {
-        let res = self
-            .client
-            .post(url)
-            .headers(headers)
-            .json(body)
-            .send()
-            .await?
-            .text()
-            .await?;
+    async fn read_response(response: Response) -> Result<(u16, String), FoxbitError> {
+        let status = response.status().as_u16();
+        let body = response.text().await?;
 
-        Ok(res)
+        if (200..300).contains(&status) {
+            return Ok((status, body));
+        }
+
+        match ApiError::from_body(status, &body) {
+            Some(api_error) => Err(FoxbitError::Api(api_error)),
+            None => Err(FoxbitError::Http { status, body }),
+        }
     }
 
-    async fn send_put_request<T: Serialize + ?Sized>(
-        &self,
-        url: &str,
-        headers: HeaderMap,
-        body: &T,
-    ) -> Result<String, reqwest::Error> {
-        let res = self
-            .client
-            .put(url)
-            .headers(headers)
-            .json(body)
-            .send()
-            .await?
-            .text()
-            .await?;
+    fn decode<T: DeserializeOwned>(body: String) -> Result<T, FoxbitError> {
+        serde_json::from_str::<T>(&body).map_err(|source| FoxbitError::Decode { source, body })
+    }
 
-        Ok(res)
+    fn encode_body<B: Serialize + ?Sized>(body: &B) -> Result<String, FoxbitError> {
+        serde_json::to_string(body)
+            .map_err(|e| FoxbitError::Validation(format!("could not encode request body: {}", e)))
     }
 
-    fn build_query_string(&self, query_params: &BTreeMap<&str, &str>) -> String {
+    fn build_url(&self, endpoint: &str, query_string: &str) -> String {
+        if query_string.is_empty() {
+            format!("{}{}", &self.base_url, endpoint)
+        } else {
+            format!("{}{}?{}", &self.base_url, endpoint, query_string)
+        }
+    }
+
+    fn build_query_string(&self, query_params: Option<&BTreeMap<&str, &str>>) -> String {
+        let Some(query_params) = query_params else {
+            return String::new();
+        };
         query_params
             .iter()
             .map(|(key, value)| format!("{}={}", key, utf8_percent_encode(value, QUERY_ENCODE_SET)))
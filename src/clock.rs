@@ -0,0 +1,115 @@
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tokio::sync::{Mutex as AsyncMutex, MutexGuard};
+
+use crate::helpers::get_local_millis;
+
+/// How often clients measure the server clock offset unless configured
+/// otherwise.
+pub const DEFAULT_CLOCK_SYNC_INTERVAL: Duration = Duration::from_secs(300);
+
+/// How long to wait before measuring the offset again after a failed attempt,
+/// unless the sync interval is shorter.
+pub const CLOCK_SYNC_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Tracks the offset between the local clock and Foxbit's server clock, so
+/// that request timestamps are not rejected when the local clock drifts.
+#[derive(Debug)]
+pub struct ServerClock {
+    offset_millis: AtomicI64,
+    round_trip_millis: AtomicU64,
+    last_sync: Mutex<Option<Instant>>,
+    last_failure: Mutex<Option<Instant>>,
+    resync_interval: Option<Duration>,
+    sync_lock: AsyncMutex<()>,
+}
+
+impl ServerClock {
+    /// Creates a clock with no offset. When `resync_interval` is set, the
+    /// offset is measured before the first signed request and again once the
+    /// interval has elapsed.
+    pub fn new(resync_interval: Option<Duration>) -> Self {
+        ServerClock {
+            offset_millis: AtomicI64::new(0),
+            round_trip_millis: AtomicU64::new(0),
+            last_sync: Mutex::new(None),
+            last_failure: Mutex::new(None),
+            resync_interval,
+            sync_lock: AsyncMutex::new(()),
+        }
+    }
+
+    /// Server time minus local time, in milliseconds.
+    pub fn offset_millis(&self) -> i64 {
+        self.offset_millis.load(Ordering::Relaxed)
+    }
+
+    /// Round-trip time of the last synchronisation, if there was one.
+    pub fn round_trip_time(&self) -> Option<Duration> {
+        self.last_synced()
+            .map(|_| Duration::from_millis(self.round_trip_millis.load(Ordering::Relaxed)))
+    }
+
+    pub fn last_synced(&self) -> Option<Instant> {
+        *self.last_sync.lock().unwrap()
+    }
+
+    /// Current server time estimate, in milliseconds since the UNIX epoch.
+    pub fn now_millis(&self) -> u64 {
+        let local = get_local_millis() as i64;
+        (local + self.offset_millis()).max(0) as u64
+    }
+
+    /// Returns `true` when periodic synchronisation is enabled and is due.
+    /// After a failed attempt, it is not due again for
+    /// [`CLOCK_SYNC_RETRY_DELAY`], so that an unavailable server time does not
+    /// add a request to every signed one.
+    pub fn needs_sync(&self) -> bool {
+        let Some(interval) = self.resync_interval else {
+            return false;
+        };
+        let last_failure = *self.last_failure.lock().unwrap();
+        if last_failure
+            .is_some_and(|failed| failed.elapsed() < interval.min(CLOCK_SYNC_RETRY_DELAY))
+        {
+            return false;
+        }
+        self.last_synced()
+            .is_none_or(|last| last.elapsed() >= interval)
+    }
+
+    /// Held while the offset is measured, so that concurrent requests do not
+    /// each query the server time.
+    pub(crate) async fn lock_sync(&self) -> MutexGuard<'_, ()> {
+        self.sync_lock.lock().await
+    }
+
+    /// Records a measurement of the server time. `sent_at` is the local time
+    /// at which the request was sent, in milliseconds since the UNIX epoch.
+    pub(crate) fn record(&self, sent_at: u64, round_trip: Duration, server_millis: u64) {
+        let round_trip_millis = round_trip.as_millis() as u64;
+        let local_midpoint = sent_at + round_trip_millis / 2;
+        let offset = server_millis as i64 - local_midpoint as i64;
+
+        self.offset_millis.store(offset, Ordering::Relaxed);
+        self.round_trip_millis
+            .store(round_trip_millis, Ordering::Relaxed);
+        *self.last_sync.lock().unwrap() = Some(Instant::now());
+        *self.last_failure.lock().unwrap() = None;
+    }
+
+    /// Records that measuring the server time failed.
+    pub(crate) fn record_failure(&self) {
+        *self.last_failure.lock().unwrap() = Some(Instant::now());
+    }
+}
+
+/// Syncs before the first signed request and then every
+/// [`DEFAULT_CLOCK_SYNC_INTERVAL`].
+impl Default for ServerClock {
+    fn default() -> Self {
+        ServerClock::new(Some(DEFAULT_CLOCK_SYNC_INTERVAL))
+    }
+}
@@ -0,0 +1,48 @@
+use dotenv::dotenv;
+use std::{env, fmt};
+
+use crate::error::FoxbitError;
+
+/// API key pair used to sign private requests.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    access_key: String,
+    api_secret: String,
+}
+
+impl Credentials {
+    pub fn new(access_key: impl Into<String>, api_secret: impl Into<String>) -> Self {
+        Credentials {
+            access_key: access_key.into(),
+            api_secret: api_secret.into(),
+        }
+    }
+
+    /// Loads the key pair from the `ACCESS_KEY` and `API_SECRET` environment
+    /// variables, reading a `.env` file first if there is one.
+    pub fn from_env() -> Result<Self, FoxbitError> {
+        dotenv().ok();
+        let access_key = env::var("ACCESS_KEY")
+            .map_err(|_| FoxbitError::Config("ACCESS_KEY is not set".into()))?;
+        let api_secret = env::var("API_SECRET")
+            .map_err(|_| FoxbitError::Config("API_SECRET is not set".into()))?;
+        Ok(Credentials::new(access_key, api_secret))
+    }
+
+    pub fn access_key(&self) -> &str {
+        &self.access_key
+    }
+
+    pub(crate) fn api_secret(&self) -> &str {
+        &self.api_secret
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("access_key", &self.access_key)
+            .field("api_secret", &"<redacted>")
+            .finish()
+    }
+}
@@ -0,0 +1,63 @@
+use crate::error::FoxbitError;
+
+/// Brazilian taxpayer document identifying the holder of a bank account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    /// Cadastro de Pessoas Físicas, 11 digits.
+    Cpf,
+    /// Cadastro Nacional da Pessoa Jurídica, 14 digits.
+    Cnpj,
+}
+
+/// Strips the usual `.`, `-` and `/` punctuation from a CPF or CNPJ.
+pub fn normalize_document(document: &str) -> String {
+    document
+        .chars()
+        .filter(|c| !matches!(c, '.' | '-' | '/' | ' '))
+        .collect()
+}
+
+/// Checks that `document` is a CPF or CNPJ with correct check digits, with or
+/// without punctuation, and returns which of the two it is.
+pub fn validate_document(document: &str) -> Result<DocumentKind, FoxbitError> {
+    let normalized = normalize_document(document);
+    let digits = normalized
+        .chars()
+        .map(|c| c.to_digit(10))
+        .collect::<Option<Vec<u32>>>()
+        .ok_or_else(|| invalid(document))?;
+
+    let kind = match digits.len() {
+        11 => DocumentKind::Cpf,
+        14 => DocumentKind::Cnpj,
+        _ => return Err(invalid(document)),
+    };
+    // Repeated digits pass the checksum but are never issued.
+    if digits.iter().all(|d| *d == digits[0]) {
+        return Err(invalid(document));
+    }
+
+    let weights: fn(usize) -> Vec<u32> = match kind {
+        DocumentKind::Cpf => |len| (2..=len as u32 + 1).rev().collect(),
+        DocumentKind::Cnpj => |len| (0..len).rev().map(|i| (i % 8) as u32 + 2).collect(),
+    };
+    let body = digits.len() - 2;
+    for len in [body, body + 1] {
+        if check_digit(&digits[..len], &weights(len)) != digits[len] {
+            return Err(invalid(document));
+        }
+    }
+    Ok(kind)
+}
+
+fn check_digit(digits: &[u32], weights: &[u32]) -> u32 {
+    let sum: u32 = digits.iter().zip(weights).map(|(d, w)| d * w).sum();
+    match sum % 11 {
+        0 | 1 => 0,
+        rest => 11 - rest,
+    }
+}
+
+fn invalid(document: &str) -> FoxbitError {
+    FoxbitError::Validation(format!("{} is not a valid CPF or CNPJ", document))
+}
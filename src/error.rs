@@ -0,0 +1,216 @@
+use serde::Deserialize;
+use std::fmt;
+use std::sync::Arc;
+
+/// Known categories of errors reported by Foxbit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiErrorKind {
+    InvalidRequest,
+    Unauthorized,
+    InvalidSignature,
+    InvalidTimestamp,
+    InsufficientBalance,
+    MarketClosed,
+    NotFound,
+    RateLimited,
+    Other,
+}
+
+impl ApiErrorKind {
+    /// Maps a Foxbit error code onto a kind, falling back to the HTTP status
+    /// when the code is missing or unknown.
+    pub fn from_code(code: Option<i64>, status: u16) -> Self {
+        match code {
+            Some(1001) => ApiErrorKind::Unauthorized,
+            Some(1002) => ApiErrorKind::InvalidSignature,
+            Some(1003) => ApiErrorKind::InvalidTimestamp,
+            Some(1004) => ApiErrorKind::RateLimited,
+            Some(2001) => ApiErrorKind::InsufficientBalance,
+            Some(2002) => ApiErrorKind::MarketClosed,
+            _ => match status {
+                400 | 422 => ApiErrorKind::InvalidRequest,
+                401 | 403 => ApiErrorKind::Unauthorized,
+                404 => ApiErrorKind::NotFound,
+                429 => ApiErrorKind::RateLimited,
+                _ => ApiErrorKind::Other,
+            },
+        }
+    }
+}
+
+/// Error payload returned by Foxbit when a request is rejected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiError {
+    pub status: u16,
+    pub kind: ApiErrorKind,
+    pub code: Option<i64>,
+    pub message: String,
+    pub details: Vec<String>,
+}
+
+impl ApiError {
+    pub fn new(status: u16, code: Option<i64>, message: String, details: Vec<String>) -> Self {
+        ApiError {
+            status,
+            kind: ApiErrorKind::from_code(code, status),
+            code,
+            message,
+            details,
+        }
+    }
+
+    /// Parses a Foxbit error body, returning `None` when it is not one.
+    pub fn from_body(status: u16, body: &str) -> Option<Self> {
+        let payload = serde_json::from_str::<ErrorPayload>(body).ok()?;
+        Some(ApiError::new(
+            status,
+            payload.error.code,
+            payload.error.message,
+            payload.error.details,
+        ))
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.code {
+            Some(code) => write!(f, "{} (code {})", self.message, code),
+            None => write!(f, "{}", self.message),
+        }?;
+        if !self.details.is_empty() {
+            write!(f, ": {}", self.details.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct ErrorPayload {
+    error: ErrorBody,
+}
+
+#[derive(Deserialize)]
+struct ErrorBody {
+    code: Option<i64>,
+    message: String,
+    #[serde(default)]
+    details: Vec<String>,
+}
+
+/// Error type returned by every call of the Foxbit client.
+#[derive(Debug)]
+pub enum FoxbitError {
+    /// The request could not be sent or the response could not be read.
+    Transport(reqwest::Error),
+    /// Foxbit answered with a non-success HTTP status and no error payload.
+    Http { status: u16, body: String },
+    /// Foxbit answered with an error payload.
+    Api(ApiError),
+    /// The response body could not be decoded into the expected type.
+    Decode {
+        source: serde_json::Error,
+        body: String,
+    },
+    /// The request was rejected locally before being sent.
+    Validation(String),
+    /// The client is not configured correctly.
+    Config(String),
+}
+
+impl FoxbitError {
+    /// Returns `true` when the same request may succeed if retried later.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            FoxbitError::Transport(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            FoxbitError::Http { status, .. } => *status >= 500 || *status == 429,
+            FoxbitError::Api(e) => e.kind == ApiErrorKind::RateLimited || e.status >= 500,
+            _ => false,
+        }
+    }
+
+    /// Returns the Foxbit error payload, if the exchange rejected the request.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            FoxbitError::Api(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FoxbitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FoxbitError::Transport(e) => write!(f, "request to Foxbit failed: {}", e),
+            FoxbitError::Http { status, body } => {
+                write!(f, "Foxbit returned HTTP {}: {}", status, body)
+            }
+            FoxbitError::Api(e) => write!(f, "Foxbit rejected the request: {}", e),
+            FoxbitError::Decode { source, .. } => {
+                write!(f, "could not decode Foxbit response: {}", source)
+            }
+            FoxbitError::Validation(msg) => write!(f, "invalid request: {}", msg),
+            FoxbitError::Config(msg) => write!(f, "invalid configuration: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for FoxbitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FoxbitError::Transport(e) => Some(e),
+            FoxbitError::Decode { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for FoxbitError {
+    fn from(e: reqwest::Error) -> Self {
+        FoxbitError::Transport(e)
+    }
+}
+
+/// Why one order of a batch was not created.
+#[derive(Debug, Clone)]
+pub enum BatchOrderFailure {
+    /// Foxbit rejected this order while accepting the rest of its batch.
+    Rejected(ApiError),
+    /// The order failed the market's trading rules and was not sent.
+    Invalid(String),
+    /// The batch carrying this order failed as a whole, or was not sent
+    /// because an earlier batch failed. The error is shared by every order
+    /// of those batches. After a transport error or a 5xx the exchange may
+    /// still have placed the failed batch, so check open orders first.
+    BatchFailed(Arc<FoxbitError>),
+}
+
+impl BatchOrderFailure {
+    /// Returns the Foxbit error payload, if the exchange rejected the order
+    /// or its batch.
+    pub fn api_error(&self) -> Option<&ApiError> {
+        match self {
+            BatchOrderFailure::Rejected(e) => Some(e),
+            BatchOrderFailure::Invalid(_) => None,
+            BatchOrderFailure::BatchFailed(e) => e.api_error(),
+        }
+    }
+}
+
+impl fmt::Display for BatchOrderFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchOrderFailure::Rejected(e) => write!(f, "Foxbit rejected the order: {}", e),
+            BatchOrderFailure::Invalid(msg) => write!(f, "invalid order: {}", msg),
+            BatchOrderFailure::BatchFailed(e) => write!(f, "order batch failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for BatchOrderFailure {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BatchOrderFailure::Rejected(_) | BatchOrderFailure::Invalid(_) => None,
+            BatchOrderFailure::BatchFailed(e) => Some(e.as_ref()),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{
+    error::FoxbitError,
+    helpers::to_amount,
+    types::{Amount, Market, Number, Side, TradeRole, TradingFee},
+};
+
+/// Expected fee of an order and the amount left once it is paid.
+///
+/// Foxbit charges the fee in the currency being received: the base currency
+/// when buying and the quote currency when selling, matching the `fee` and
+/// `fee_currency_symbol` fields of a [`Trade`](crate::types::Trade).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeeEstimate {
+    pub fee: Amount,
+    pub net_amount: Amount,
+    pub fee_currency: String,
+}
+
+/// Estimates the fee of an order of `quantity` at `price` on `market`.
+pub fn estimate_fee(
+    market: &Market,
+    trading_fee: &TradingFee,
+    side: Side,
+    role: TradeRole,
+    quantity: &Number,
+    price: &Number,
+) -> Result<FeeEstimate, FoxbitError> {
+    let rate = match role {
+        TradeRole::Maker => to_amount("maker fee", &trading_fee.maker)?,
+        TradeRole::Taker => to_amount("taker fee", &trading_fee.taker)?,
+        TradeRole::Unknown(_) => {
+            return Err(FoxbitError::Validation(format!("unknown role {}", role)))
+        }
+    };
+    let quantity = to_amount("quantity", quantity)?;
+    let price = to_amount("price", price)?;
+
+    let (gross, currency) = match side {
+        Side::Buy => (quantity, &market.base),
+        Side::Sell => (quantity * price, &market.quote),
+        Side::Unknown(_) => return Err(FoxbitError::Validation(format!("unknown side {}", side))),
+    };
+    let fee_currency = currency
+        .symbol
+        .clone()
+        .ok_or_else(|| FoxbitError::Validation("market currency has no symbol".into()))?;
+
+    let fee = gross * rate;
+    Ok(FeeEstimate {
+        fee,
+        net_amount: gross - fee,
+        fee_currency,
+    })
+}
@@ -1,44 +1,154 @@
 use crate::{
     api::Api,
+    clock::{ServerClock, DEFAULT_CLOCK_SYNC_INTERVAL},
+    credentials::Credentials,
+    document::{normalize_document, validate_document},
+    error::{BatchOrderFailure, FoxbitError},
+    limits::{check_limits, LimitWarning},
+    pagination::{paginate, PageBudget, DEFAULT_STREAM_PAGE_SIZE},
+    portfolio::{value_portfolio, PortfolioValuation},
+    registry::{MarketRegistry, DEFAULT_MARKET_CACHE_TTL},
     types::{
-        Bank, CancelOrderResponse, Candlestick, CreateOrderResponse, Currency, CurrentTime, Market,
-        MemberDetails, Order, OrderBook, Quote, Trade,
+        Account, Bank, BankAccount, BatchOrderResult, CancelMode, CancelOrderResponse,
+        CancelReplaceResponse, CandleInterval, Candlestick, CreateOrderResponse, Currency,
+        CurrentTime, Deposit, DepositAddress, DepositFilter, LimitOperation, Market, MemberDetails,
+        Number, Order, OrderBook, OrderFilter, OrderRef, OrderRequest, OrderState, OrderType,
+        OtcExecution, OtcExecutionFilter, OtcMarket, OtcQuote, OtcQuoteRequest, PublicTrade, Quote,
+        Side, Ticker, Trade, TradeFilter, TradingFee, TransactionalLimit, Withdrawal,
+        WithdrawalFilter, WithdrawalRequest,
     },
+    validation::{compare_numbers, normalize_order, subtract_numbers, RoundingMode},
 };
-use dotenv::dotenv;
+use futures::Stream;
 use reqwest::Client;
-use std::env;
+use std::cmp::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
 
-pub struct Foxbit {
+/// Base URL of the Foxbit v3 REST API.
+pub const DEFAULT_API_URL: &str = "https://api.foxbit.com.br/rest/v3";
+
+/// Maximum number of orders Foxbit accepts in one batch request.
+pub const MAX_BATCH_ORDERS: usize = 20;
+
+/// Client state without credentials; only market data endpoints are available.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Public;
+
+/// Client state holding the credentials used to sign private requests.
+#[derive(Debug, Clone)]
+pub struct Authenticated {
+    credentials: Credentials,
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for super::Public {}
+    impl Sealed for super::Authenticated {}
+}
+
+/// Access level of a [`Foxbit`] client, either [`Public`] or [`Authenticated`].
+pub trait Access: sealed::Sealed {
+    fn credentials(&self) -> Option<&Credentials>;
+}
+
+impl Access for Public {
+    fn credentials(&self) -> Option<&Credentials> {
+        None
+    }
+}
+
+impl Access for Authenticated {
+    fn credentials(&self) -> Option<&Credentials> {
+        Some(&self.credentials)
+    }
+}
+
+pub struct Foxbit<S = Authenticated> {
     http_client: Client,
     api_url: String,
+    access: S,
+    clock: Arc<ServerClock>,
+    order_validation: Option<RoundingMode>,
+    markets: MarketRegistry,
+    page_budget: PageBudget,
 }
 
-impl Foxbit {
-    pub fn new(http_client: Client, api_url: String) -> Self {
+impl Foxbit<Public> {
+    /// Creates a client for public market data that holds no API keys.
+    pub fn public(http_client: Client, api_url: String) -> Self {
+        let clock = Arc::new(ServerClock::default());
+        let markets = MarketRegistry::with_clock(
+            http_client.clone(),
+            api_url.clone(),
+            Arc::clone(&clock),
+            DEFAULT_MARKET_CACHE_TTL,
+        );
         Foxbit {
             http_client,
             api_url,
+            access: Public,
+            clock,
+            order_validation: Some(RoundingMode::default()),
+            markets,
+            page_budget: PageBudget::default(),
+        }
+    }
+
+    pub fn builder() -> FoxbitBuilder<Public> {
+        FoxbitBuilder::default()
+    }
+
+    /// Upgrades the client so that private endpoints can be called.
+    pub fn authenticate(self, credentials: Credentials) -> Foxbit<Authenticated> {
+        Foxbit {
+            http_client: self.http_client,
+            api_url: self.api_url,
+            access: Authenticated { credentials },
+            clock: self.clock,
+            order_validation: self.order_validation,
+            markets: self.markets,
+            page_budget: self.page_budget,
         }
     }
+}
+
+impl<S: Access> Foxbit<S> {
+    fn api(&self) -> Api<'_> {
+        Api::new(
+            &self.http_client,
+            &self.api_url,
+            self.access.credentials(),
+            &self.clock,
+        )
+    }
 
-    pub async fn list_currencies(&self) -> Result<Vec<Currency>, serde_json::Error> {
-        dotenv().ok();
-        let api_secret = env::var("API_SECRET").expect("API secret not found");
-        let access_key = env::var("ACCESS_KEY").expect("Access key not found");
+    /// Cached markets and currencies, shared by order validation and the
+    /// withdrawal checks.
+    pub fn market_registry(&self) -> &MarketRegistry {
+        &self.markets
+    }
 
-        let api = Api::new(&self.http_client, &self.api_url, api_secret, access_key);
+    /// Offset and round-trip time measured against the server clock.
+    pub fn clock(&self) -> &ServerClock {
+        &self.clock
+    }
+
+    /// Measures the server clock offset now, e.g. right after start-up.
+    pub async fn sync_clock(&self) -> Result<(), FoxbitError> {
+        self.api().sync_clock().await
+    }
+
+    pub async fn list_currencies(&self) -> Result<Vec<Currency>, FoxbitError> {
+        let api = self.api();
 
         let currencies = api.list_currencies().await;
         currencies
     }
 
-    pub async fn list_markets(&self) -> Result<Vec<Market>, serde_json::Error> {
-        dotenv().ok();
-        let api_secret = env::var("API_SECRET").expect("API secret not found");
-        let access_key = env::var("ACCESS_KEY").expect("Access key not found");
-
-        let api = Api::new(&self.http_client, &self.api_url, api_secret, access_key);
+    pub async fn list_markets(&self) -> Result<Vec<Market>, FoxbitError> {
+        let api = self.api();
 
         let markets = api.list_markets().await;
         markets
@@ -46,17 +156,13 @@ impl Foxbit {
 
     pub async fn get_market_quotation(
         &self,
-        side: &str,
+        side: Side,
         base_currency: &str,
         quote_currency: &str,
         quantity: Option<&str>,
         amount: Option<&str>,
-    ) -> Result<Quote, serde_json::Error> {
-        dotenv().ok();
-        let api_secret = env::var("API_SECRET").expect("API secret not found");
-        let access_key = env::var("ACCESS_KEY").expect("Access key not found");
-
-        let api = Api::new(&self.http_client, &self.api_url, api_secret, access_key);
+    ) -> Result<Quote, FoxbitError> {
+        let api = self.api();
 
         let quote = api
             .get_market_quotation(side, base_currency, quote_currency, quantity, amount)
@@ -68,91 +174,223 @@ impl Foxbit {
         &self,
         market_symbol: &str,
         depth: u8,
-    ) -> Result<OrderBook, serde_json::Error> {
-        dotenv().ok();
-        let api_secret = env::var("API_SECRET").expect("API secret not found");
-        let access_key = env::var("ACCESS_KEY").expect("Access key not found");
-
-        let api = Api::new(&self.http_client, &self.api_url, api_secret, access_key);
+    ) -> Result<OrderBook, FoxbitError> {
+        let api = self.api();
         let order_book = api.get_order_book(market_symbol, depth).await;
         order_book
     }
-    
+
     pub async fn get_candlesticks(
         &self,
         market_symbol: &str,
-        interval: &str,
+        interval: CandleInterval,
         start_time: &str,
         end_time: &str,
-    ) -> Result<Vec<Candlestick>, serde_json::Error> {
-        dotenv().ok();
-        let api_secret = env::var("API_SECRET").expect("API secret not found");
-        let access_key = env::var("ACCESS_KEY").expect("Access key not found");
-
-        let api = Api::new(&self.http_client, &self.api_url, api_secret, access_key);
+    ) -> Result<Vec<Candlestick>, FoxbitError> {
+        let api = self.api();
         let candlesticks = api
             .get_candlesticks(market_symbol, interval, start_time, end_time)
             .await;
         candlesticks
     }
 
-    pub async fn list_banks(&self) -> Result<Vec<Bank>, serde_json::Error> {
-        dotenv().ok();
-        let api_secret = env::var("API_SECRET").expect("API secret not found");
-        let access_key = env::var("ACCESS_KEY").expect("Access key not found");
+    pub async fn get_ticker(&self, market_symbol: &str) -> Result<Ticker, FoxbitError> {
+        let api = self.api();
+        let ticker = api.get_ticker(market_symbol).await;
+        ticker
+    }
+
+    pub async fn list_tickers(&self) -> Result<Vec<Ticker>, FoxbitError> {
+        let api = self.api();
+        let tickers = api.list_tickers().await;
+        tickers
+    }
 
-        let api = Api::new(&self.http_client, &self.api_url, api_secret, access_key);
+    /// Lists recent trades of a market, optionally filtered by time and
+    /// paginated.
+    pub async fn get_market_trades(
+        &self,
+        market_symbol: &str,
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+        page_size: Option<usize>,
+        page: Option<usize>,
+    ) -> Result<Vec<PublicTrade>, FoxbitError> {
+        let api = self.api();
+        let trades = api
+            .get_market_trades(market_symbol, start_time, end_time, page_size, page)
+            .await;
+        trades
+    }
+
+    pub async fn list_banks(&self) -> Result<Vec<Bank>, FoxbitError> {
+        let api = self.api();
         let banks = api.list_banks().await;
         banks
     }
 
-    pub async fn get_current_time(&self) -> Result<CurrentTime, serde_json::Error> {
-        dotenv().ok();
-        let api_secret = env::var("API_SECRET").expect("API secret not found");
-        let access_key = env::var("ACCESS_KEY").expect("Access key not found");
-
-        let api = Api::new(&self.http_client, &self.api_url, api_secret, access_key);
+    pub async fn get_current_time(&self) -> Result<CurrentTime, FoxbitError> {
+        let api = self.api();
         let current_time = api.get_current_time().await;
         current_time
     }
+}
 
-    pub async fn get_current_member_details(&self) -> Result<MemberDetails, serde_json::Error> {
-        dotenv().ok();
-        let api_secret = env::var("API_SECRET").expect("API secret not found");
-        let access_key = env::var("ACCESS_KEY").expect("Access key not found");
+impl Foxbit<Authenticated> {
+    pub fn new(http_client: Client, api_url: String, credentials: Credentials) -> Self {
+        let clock = Arc::new(ServerClock::default());
+        let markets = MarketRegistry::with_clock(
+            http_client.clone(),
+            api_url.clone(),
+            Arc::clone(&clock),
+            DEFAULT_MARKET_CACHE_TTL,
+        );
+        Foxbit {
+            http_client,
+            api_url,
+            access: Authenticated { credentials },
+            clock,
+            order_validation: Some(RoundingMode::default()),
+            markets,
+            page_budget: PageBudget::default(),
+        }
+    }
 
-        let api = Api::new(&self.http_client, &self.api_url, api_secret, access_key);
+    pub async fn get_current_member_details(&self) -> Result<MemberDetails, FoxbitError> {
+        let api = self.api();
         let member_details = api.get_current_member_details().await;
         member_details
     }
 
+    /// Lists the maker and taker fee rates applied to the member, per market.
+    pub async fn get_trading_fees(&self) -> Result<Vec<TradingFee>, FoxbitError> {
+        let api = self.api();
+        let trading_fees = api.get_trading_fees().await;
+        trading_fees
+    }
+
+    pub async fn list_transactional_limits(&self) -> Result<Vec<TransactionalLimit>, FoxbitError> {
+        let api = self.api();
+        let limits = api.list_transactional_limits().await;
+        limits
+    }
+
+    /// Checks a deposit or withdrawal against the remaining limits of the
+    /// member's level. An empty list means it fits every limit; see
+    /// [`check_limits`] for how limits are matched.
+    pub async fn check_transactional_limits(
+        &self,
+        currency_symbol: &str,
+        operation: LimitOperation,
+        amount: impl Into<Number>,
+    ) -> Result<Vec<LimitWarning>, FoxbitError> {
+        let api = self.api();
+        let (member, limits) = tokio::try_join!(
+            api.get_current_member_details(),
+            api.list_transactional_limits()
+        )?;
+        check_limits(&limits, &member, currency_symbol, operation, &amount.into())
+    }
+
     pub async fn create_order(
         &self,
-        side: &str,
-        r#type: &str,
+        side: Side,
+        r#type: OrderType,
         market_symbol: &str,
-        quantity: &str,
+        quantity: impl Into<Number>,
         client_order_id: Option<&str>,
         remark: Option<&str>,
-    ) -> Result<CreateOrderResponse, serde_json::Error> {
-        dotenv().ok();
-        let api_secret = env::var("API_SECRET").expect("API secret not found");
-        let access_key = env::var("ACCESS_KEY").expect("Access key not found");
-
-        let api = Api::new(&self.http_client, &self.api_url, api_secret, access_key);
-        let response = api
-            .create_order(
-                side,
-                r#type,
-                market_symbol,
-                quantity,
-                client_order_id,
-                remark,
-            )
-            .await;
+    ) -> Result<CreateOrderResponse, FoxbitError> {
+        let mut order = OrderRequest::new(side, r#type, market_symbol, quantity);
+        order.client_order_id = client_order_id.map(str::to_string);
+        order.remark = remark.map(str::to_string);
+
+        self.submit_order(&order).await
+    }
+
+    /// Creates an order built with [`OrderRequest::builder`], which supports
+    /// every order type and option.
+    ///
+    /// Unless turned off on the builder, the order is first checked against
+    /// its market's trading rules and its numbers are snapped to the market's
+    /// increments; see [`normalize_order`].
+    pub async fn submit_order(
+        &self,
+        order: &OrderRequest,
+    ) -> Result<CreateOrderResponse, FoxbitError> {
+        let api = self.api();
+        let order = self.prepare_order(order).await?;
+        let response = api.submit_order(&order).await;
         response
     }
 
+    /// Creates several orders with one signed request per batch of at most
+    /// [`MAX_BATCH_ORDERS`] orders. Each result is matched to its request by
+    /// position and carries the request's `client_order_id`.
+    ///
+    /// Orders that fail the market's trading rules are marked with
+    /// [`BatchOrderFailure::Invalid`] and left out of the batches; the others
+    /// are still sent.
+    ///
+    /// Batches are submitted in sequence. When a batch fails as a whole, its
+    /// orders and those of the batches not yet sent are marked with
+    /// [`BatchOrderFailure::BatchFailed`], while the results of earlier batches
+    /// are kept, so that the caller can tell which orders are live.
+    pub async fn create_orders_batch(
+        &self,
+        orders: Vec<OrderRequest>,
+    ) -> Result<Vec<BatchOrderResult>, FoxbitError> {
+        let api = self.api();
+        let prepared = self.prepare_orders(&orders).await?;
+
+        let mut results = Vec::with_capacity(orders.len());
+        let mut positions = Vec::new();
+        let mut valid = Vec::new();
+        for (position, order) in prepared.into_iter().enumerate() {
+            match order {
+                Ok(order) => {
+                    results.push(None);
+                    positions.push(position);
+                    valid.push(order);
+                }
+                Err(FoxbitError::Validation(msg)) => {
+                    results.push(Some(Err(BatchOrderFailure::Invalid(msg))))
+                }
+                Err(e) => results.push(Some(Err(BatchOrderFailure::Invalid(e.to_string())))),
+            }
+        }
+
+        let mut sent = 0;
+        for chunk in valid.chunks(MAX_BATCH_ORDERS) {
+            match api.create_orders_batch(chunk).await {
+                Ok(chunk_results) => {
+                    for chunk_result in chunk_results {
+                        results[positions[sent]] = Some(chunk_result.result);
+                        sent += 1;
+                    }
+                }
+                Err(e) => {
+                    let error = Arc::new(e);
+                    for &position in &positions[sent..] {
+                        results[position] =
+                            Some(Err(BatchOrderFailure::BatchFailed(Arc::clone(&error))));
+                    }
+                    break;
+                }
+            }
+        }
+
+        Ok(orders
+            .iter()
+            .zip(results)
+            .map(|(order, result)| BatchOrderResult {
+                client_order_id: order.client_order_id.clone(),
+                result: result.expect("every order is invalid, sent or marked as failed"),
+            })
+            .collect())
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn list_orders(
         &self,
         start_time: &str,
@@ -160,14 +398,10 @@ impl Foxbit {
         page_size: usize,
         page: usize,
         market_symbol: &str,
-        state: &str,
-        side: &str,
-    ) -> Result<Vec<Order>, serde_json::Error> {
-        dotenv().ok();
-        let api_secret = env::var("API_SECRET").expect("API secret not found");
-        let access_key = env::var("ACCESS_KEY").expect("Access key not found");
-
-        let api = Api::new(&self.http_client, &self.api_url, api_secret, access_key);
+        state: OrderState,
+        side: Side,
+    ) -> Result<Vec<Order>, FoxbitError> {
+        let api = self.api();
         let orders = api
             .list_orders(
                 start_time,
@@ -182,12 +416,29 @@ impl Foxbit {
         orders
     }
 
-    pub async fn get_order_by_id(&self, order_id: &str) -> Result<Order, serde_json::Error> {
-        dotenv().ok();
-        let api_secret = env::var("API_SECRET").expect("API secret not found");
-        let access_key = env::var("ACCESS_KEY").expect("Access key not found");
+    /// Streams the member's orders matching `filter`, fetching pages lazily
+    /// from `filter.page` (1 by default) until one comes back short. Pages
+    /// are requested within the client's [`PageBudget`], and the stream ends
+    /// after yielding the first error.
+    pub fn orders_stream(
+        &self,
+        filter: &OrderFilter,
+    ) -> impl Stream<Item = Result<Order, FoxbitError>> + '_ {
+        let page_size = filter.page_size.unwrap_or(DEFAULT_STREAM_PAGE_SIZE).max(1);
+        let first_page = filter.page.unwrap_or(1);
+        let filter = filter.clone();
+        paginate(first_page, page_size, self.page_budget, move |page| {
+            let filter = OrderFilter {
+                page_size: Some(page_size),
+                page: Some(page),
+                ..filter.clone()
+            };
+            async move { self.api().list_orders_page(&filter).await }
+        })
+    }
 
-        let api = Api::new(&self.http_client, &self.api_url, api_secret, access_key);
+    pub async fn get_order_by_id(&self, order_id: &str) -> Result<Order, FoxbitError> {
+        let api = self.api();
         let order = api.get_order_by_id(order_id).await;
         order
     }
@@ -195,29 +446,122 @@ impl Foxbit {
     pub async fn get_order_by_client_id(
         &self,
         client_order_id: &str,
-    ) -> Result<Order, serde_json::Error> {
-        dotenv().ok();
-        let api_secret = env::var("API_SECRET").expect("API secret not found");
-        let access_key = env::var("ACCESS_KEY").expect("Access key not found");
-
-        let api = Api::new(&self.http_client, &self.api_url, api_secret, access_key);
+    ) -> Result<Order, FoxbitError> {
+        let api = self.api();
         let order = api.get_order_by_client_id(client_order_id).await;
         order
     }
 
+    /// Cancels a single order, every order of a market or all open orders.
     pub async fn cancel_orders(
         &self,
-        r#type: &str,
-    ) -> Result<Vec<CancelOrderResponse>, serde_json::Error> {
-        dotenv().ok();
-        let api_secret = env::var("API_SECRET").expect("API secret not found");
-        let access_key = env::var("ACCESS_KEY").expect("Access key not found");
-
-        let api = Api::new(&self.http_client, &self.api_url, api_secret, access_key);
-        let cancel_order_response = api.cancel_orders(r#type).await;
+        mode: CancelMode,
+    ) -> Result<Vec<CancelOrderResponse>, FoxbitError> {
+        let api = self.api();
+        let cancel_order_response = api.cancel_orders(&mode).await;
         cancel_order_response
     }
 
+    /// Replaces an open order with one at a new price and/or quantity, without
+    /// leaving the market unquoted between the cancellation and the creation.
+    ///
+    /// The replacement is rebuilt from the original order: it keeps its side,
+    /// market and `client_order_id`, and the price that is not given. Once
+    /// part of the order has been filled, the new quantity must be given and
+    /// may not exceed what is left, so that the filled part is not posted
+    /// again; orders with nothing left are rejected. Foxbit does not report
+    /// `post_only` or `time_in_force` for an order, so these are not carried
+    /// over; use [`cancel_and_replace_with`](Self::cancel_and_replace_with) to
+    /// set them. Only limit orders rest on the book in a form that can be
+    /// rebuilt, so other types are rejected.
+    pub async fn cancel_and_replace(
+        &self,
+        order: OrderRef,
+        price: Option<Number>,
+        quantity: Option<Number>,
+    ) -> Result<CancelReplaceResponse, FoxbitError> {
+        if price.is_none() && quantity.is_none() {
+            return Err(FoxbitError::Validation(
+                "Must receive a new price or quantity".into(),
+            ));
+        }
+
+        let api = self.api();
+        let original = match &order {
+            OrderRef::Id { id } => api.get_order_by_id(&id.to_string()).await?,
+            OrderRef::ClientOrderId { client_order_id } => {
+                api.get_order_by_client_id(client_order_id).await?
+            }
+        };
+        // Market orders never rest on the book, and the stop price of stop
+        // orders is not reported, so only limit orders can be rebuilt.
+        if original.r#type != OrderType::Limit {
+            return Err(FoxbitError::Validation(format!(
+                "only LIMIT orders can be replaced, got {}; use cancel_and_replace_with",
+                original.r#type
+            )));
+        }
+
+        let remaining =
+            subtract_numbers("quantity", &original.quantity, &original.quantity_executed)?;
+        if compare_numbers("quantity", &remaining, "0")? != Ordering::Greater {
+            return Err(FoxbitError::Validation(format!(
+                "order {} has nothing left to replace",
+                original.id
+            )));
+        }
+        let partially_filled =
+            compare_numbers("quantity_executed", &original.quantity_executed, "0")?
+                == Ordering::Greater;
+        let quantity = match quantity {
+            Some(quantity) => {
+                if partially_filled
+                    && compare_numbers("quantity", &quantity, &remaining)? == Ordering::Greater
+                {
+                    return Err(FoxbitError::Validation(format!(
+                        "quantity {} exceeds the {} left of order {}",
+                        quantity, remaining, original.id
+                    )));
+                }
+                quantity
+            }
+            None if partially_filled => {
+                return Err(FoxbitError::Validation(format!(
+                    "order {} is partially filled; give a new quantity of at most {}",
+                    original.id, remaining
+                )));
+            }
+            None => remaining,
+        };
+
+        let mut replacement = OrderRequest::new(
+            original.side,
+            original.r#type,
+            &original.market_symbol,
+            quantity,
+        );
+        replacement.price = price.or(original.price);
+        replacement.client_order_id = original.client_order_id.filter(|id| !id.is_empty());
+
+        self.cancel_and_replace_with(order, &replacement).await
+    }
+
+    /// Cancels `order` and creates `replacement` in one request, like
+    /// [`cancel_and_replace`](Self::cancel_and_replace) but with every field
+    /// of the new order given by the caller, e.g. with
+    /// [`OrderRequest::builder`].
+    pub async fn cancel_and_replace_with(
+        &self,
+        order: OrderRef,
+        replacement: &OrderRequest,
+    ) -> Result<CancelReplaceResponse, FoxbitError> {
+        let api = self.api();
+        let replacement = self.prepare_order(replacement).await?;
+
+        let response = api.cancel_and_replace(&order, &replacement).await;
+        response
+    }
+
     pub async fn list_trades(
         &self,
         start_time: &str,
@@ -225,15 +569,390 @@ impl Foxbit {
         page_size: usize,
         page: usize,
         market_symbol: &str,
-    ) -> Result<Vec<Trade>, serde_json::Error> {
-        dotenv().ok();
-        let api_secret = env::var("API_SECRET").expect("API secret not found");
-        let access_key = env::var("ACCESS_KEY").expect("Access key not found");
-
-        let api = Api::new(&self.http_client, &self.api_url, api_secret, access_key);
+    ) -> Result<Vec<Trade>, FoxbitError> {
+        let api = self.api();
         let listed_trades = api
             .list_trades(start_time, end_time, page_size, page, market_symbol)
             .await;
         listed_trades
     }
+
+    /// Streams the member's trades matching `filter`. See
+    /// [`orders_stream`](Self::orders_stream) for how pages are fetched.
+    pub fn trades_stream(
+        &self,
+        filter: &TradeFilter,
+    ) -> impl Stream<Item = Result<Trade, FoxbitError>> + '_ {
+        let page_size = filter.page_size.unwrap_or(DEFAULT_STREAM_PAGE_SIZE).max(1);
+        let first_page = filter.page.unwrap_or(1);
+        let filter = filter.clone();
+        paginate(first_page, page_size, self.page_budget, move |page| {
+            let filter = TradeFilter {
+                page_size: Some(page_size),
+                page: Some(page),
+                ..filter.clone()
+            };
+            async move { self.api().list_trades_page(&filter).await }
+        })
+    }
+
+    /// Lists the member's balances, one account per currency.
+    pub async fn list_accounts(&self) -> Result<Vec<Account>, FoxbitError> {
+        let api = self.api();
+        let accounts = api.list_accounts().await;
+        accounts
+    }
+
+    pub async fn list_deposits(&self, filter: &DepositFilter) -> Result<Vec<Deposit>, FoxbitError> {
+        let api = self.api();
+        let deposits = api.list_deposits(filter).await;
+        deposits
+    }
+
+    pub async fn get_deposit(&self, sn: &str) -> Result<Deposit, FoxbitError> {
+        let api = self.api();
+        let deposit = api.get_deposit(sn).await;
+        deposit
+    }
+
+    /// Gets the address to deposit `currency_symbol` to, on `network_code` or
+    /// on the currency's default network.
+    pub async fn get_deposit_address(
+        &self,
+        currency_symbol: &str,
+        network_code: Option<&str>,
+    ) -> Result<DepositAddress, FoxbitError> {
+        let api = self.api();
+        let deposit_address = api.get_deposit_address(currency_symbol, network_code).await;
+        deposit_address
+    }
+
+    pub async fn list_withdrawals(
+        &self,
+        filter: &WithdrawalFilter,
+    ) -> Result<Vec<Withdrawal>, FoxbitError> {
+        let api = self.api();
+        let withdrawals = api.list_withdrawals(filter).await;
+        withdrawals
+    }
+
+    /// Creates a withdrawal after checking its amount against the currency's
+    /// `withdraw_info`, so that withdrawals Foxbit would reject fail locally.
+    /// Exactly one destination must be set. Bank account destinations must
+    /// also have a valid CPF/CNPJ, which is sent without punctuation, and a
+    /// bank code listed by `list_banks`.
+    pub async fn create_withdrawal(
+        &self,
+        request: &WithdrawalRequest,
+    ) -> Result<Withdrawal, FoxbitError> {
+        match (
+            &request.destination_crypto,
+            &request.destination_bank_account,
+        ) {
+            (Some(_), None) | (None, Some(_)) => {}
+            _ => {
+                return Err(FoxbitError::Validation(
+                    "Must receive exactly one of destination_crypto or destination_bank_account"
+                        .into(),
+                ))
+            }
+        }
+        let mut request = request.clone();
+        if let Some(bank_account) = request.destination_bank_account.as_mut() {
+            bank_account.holder_document = normalize_document(&bank_account.holder_document);
+        }
+
+        let api = self.api();
+        let snapshot = self.markets.snapshot().await?;
+        let currency = snapshot.require_currency(&request.currency_symbol)?;
+        if let Some(withdraw_info) = &currency.withdraw_info {
+            withdraw_info.check_amount(&request.amount)?;
+        }
+        if let Some(bank_account) = &request.destination_bank_account {
+            validate_document(&bank_account.holder_document)?;
+            let banks = api.list_banks().await?;
+            if !banks.iter().any(|bank| bank.code == bank_account.bank_code) {
+                return Err(FoxbitError::Validation(format!(
+                    "Unknown bank code: {}",
+                    bank_account.bank_code
+                )));
+            }
+        }
+
+        let withdrawal = api.create_withdrawal(&request).await;
+        withdrawal
+    }
+
+    /// Withdraws `amount` BRL to `bank_account`. See `create_withdrawal` for
+    /// the checks made before sending it.
+    pub async fn create_fiat_withdrawal(
+        &self,
+        amount: impl Into<Number>,
+        bank_account: BankAccount,
+    ) -> Result<Withdrawal, FoxbitError> {
+        self.create_withdrawal(&WithdrawalRequest::fiat(amount, bank_account))
+            .await
+    }
+
+    pub async fn get_withdrawal(&self, sn: &str) -> Result<Withdrawal, FoxbitError> {
+        let api = self.api();
+        let withdrawal = api.get_withdrawal(sn).await;
+        withdrawal
+    }
+
+    pub async fn list_otc_markets(&self) -> Result<Vec<OtcMarket>, FoxbitError> {
+        let api = self.api();
+        let otc_markets = api.list_otc_markets().await;
+        otc_markets
+    }
+
+    pub async fn request_otc_quote(
+        &self,
+        request: &OtcQuoteRequest,
+    ) -> Result<OtcQuote, FoxbitError> {
+        let api = self.api();
+        let quote = api.request_otc_quote(request).await;
+        quote
+    }
+
+    /// Executes an OTC quote. Quotes that have already expired by the server
+    /// clock are rejected locally, as the desk would refuse them.
+    pub async fn execute_otc_quote(&self, quote: &OtcQuote) -> Result<OtcExecution, FoxbitError> {
+        let api = self.api();
+        // Without a fresh offset, the last known one is close enough; the
+        // desk still rejects quotes that have expired.
+        let _ = api.sync_clock_if_due().await;
+        let now = self.clock.now_millis();
+        if quote.is_expired_at(now) {
+            return Err(FoxbitError::Validation(format!(
+                "OTC quote {} expired {} ms ago",
+                quote.quote_id,
+                now - quote.expires_at
+            )));
+        }
+
+        let execution = api.execute_otc_quote(&quote.quote_id).await;
+        execution
+    }
+
+    pub async fn list_otc_executions(
+        &self,
+        filter: &OtcExecutionFilter,
+    ) -> Result<Vec<OtcExecution>, FoxbitError> {
+        let api = self.api();
+        let executions = api.list_otc_executions(filter).await;
+        executions
+    }
+
+    /// Values every balance in `quote_currency` at the last traded price of
+    /// its market. See [`value_portfolio`] for how prices are found.
+    pub async fn value_portfolio(
+        &self,
+        quote_currency: &str,
+    ) -> Result<PortfolioValuation, FoxbitError> {
+        let api = self.api();
+        let (accounts, markets, tickers) =
+            tokio::try_join!(api.list_accounts(), api.list_markets(), api.list_tickers())?;
+        value_portfolio(&accounts, &markets, &tickers, quote_currency)
+    }
+
+    /// Normalizes `orders` against the trading rules of their markets, or
+    /// returns them unchanged when order validation is turned off.
+    ///
+    /// Each order is checked on its own; the call itself only fails when the
+    /// markets cannot be loaded.
+    async fn prepare_orders(
+        &self,
+        orders: &[OrderRequest],
+    ) -> Result<Vec<Result<OrderRequest, FoxbitError>>, FoxbitError> {
+        let rounding = match self.order_validation {
+            Some(rounding) => rounding,
+            None => return Ok(orders.iter().cloned().map(Ok).collect()),
+        };
+        let snapshot = self.markets.snapshot().await?;
+        Ok(orders
+            .iter()
+            .map(|order| {
+                let market = snapshot.require_market(&order.market_symbol)?;
+                normalize_order(order, market, rounding)
+            })
+            .collect())
+    }
+
+    async fn prepare_order(&self, order: &OrderRequest) -> Result<OrderRequest, FoxbitError> {
+        let mut prepared = self.prepare_orders(std::slice::from_ref(order)).await?;
+        prepared.remove(0)
+    }
+}
+
+/// Configures and creates a [`Foxbit`] client. Setting credentials makes the
+/// builder produce an authenticated client.
+#[derive(Default)]
+pub struct FoxbitBuilder<S = Public> {
+    api_url: Option<String>,
+    access: S,
+    http_client: Option<Client>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    user_agent: Option<String>,
+    clock_sync_interval: Option<Duration>,
+    skip_clock_sync: bool,
+    order_rounding: Option<RoundingMode>,
+    skip_order_validation: bool,
+    market_cache_ttl: Option<Duration>,
+    page_budget: Option<PageBudget>,
+}
+
+impl FoxbitBuilder<Public> {
+    pub fn credentials(self, credentials: Credentials) -> FoxbitBuilder<Authenticated> {
+        FoxbitBuilder {
+            api_url: self.api_url,
+            access: Authenticated { credentials },
+            http_client: self.http_client,
+            timeout: self.timeout,
+            connect_timeout: self.connect_timeout,
+            user_agent: self.user_agent,
+            clock_sync_interval: self.clock_sync_interval,
+            skip_clock_sync: self.skip_clock_sync,
+            order_rounding: self.order_rounding,
+            skip_order_validation: self.skip_order_validation,
+            market_cache_ttl: self.market_cache_ttl,
+            page_budget: self.page_budget,
+        }
+    }
+}
+
+impl<S: Access> FoxbitBuilder<S> {
+    /// Overrides the API base URL, which defaults to [`DEFAULT_API_URL`].
+    pub fn api_url(mut self, api_url: impl Into<String>) -> Self {
+        self.api_url = Some(api_url.into());
+        self
+    }
+
+    /// Uses an existing HTTP client instead of creating one. Timeouts and the
+    /// user agent must then be configured on that client.
+    pub fn http_client(mut self, http_client: Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Synchronises request timestamps with the server clock before the first
+    /// signed request and then every `interval`. Defaults to
+    /// [`DEFAULT_CLOCK_SYNC_INTERVAL`].
+    pub fn clock_sync(mut self, interval: Duration) -> Self {
+        self.clock_sync_interval = Some(interval);
+        self
+    }
+
+    /// Never measures the server clock on a schedule; request timestamps use
+    /// the local clock until Foxbit rejects one.
+    pub fn skip_clock_sync(mut self) -> Self {
+        self.skip_clock_sync = true;
+        self
+    }
+
+    /// Rounding used to snap order prices and quantities to the market's
+    /// increments before they are submitted. Defaults to
+    /// [`RoundingMode::Down`].
+    pub fn order_rounding(mut self, rounding: RoundingMode) -> Self {
+        self.order_rounding = Some(rounding);
+        self
+    }
+
+    /// Submits orders as given, without checking them against the market's
+    /// trading rules first.
+    pub fn skip_order_validation(mut self) -> Self {
+        self.skip_order_validation = true;
+        self
+    }
+
+    /// How long markets and currencies are cached before being refreshed in
+    /// the background. Defaults to [`DEFAULT_MARKET_CACHE_TTL`].
+    pub fn market_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.market_cache_ttl = Some(ttl);
+        self
+    }
+
+    /// Concurrency and pacing of `orders_stream` and `trades_stream`.
+    /// Defaults to one page at a time, at most ten per second.
+    pub fn page_budget(mut self, budget: PageBudget) -> Self {
+        self.page_budget = Some(budget);
+        self
+    }
+
+    pub fn build(self) -> Result<Foxbit<S>, FoxbitError> {
+        let api_url = self.api_url.unwrap_or_else(|| DEFAULT_API_URL.to_string());
+
+        let http_client = match self.http_client {
+            Some(client) => {
+                if self.timeout.is_some()
+                    || self.connect_timeout.is_some()
+                    || self.user_agent.is_some()
+                {
+                    return Err(FoxbitError::Config(
+                        "timeouts and user agent cannot be applied to a custom HTTP client".into(),
+                    ));
+                }
+                client
+            }
+            None => {
+                let mut builder = Client::builder();
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(connect_timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(connect_timeout);
+                }
+                if let Some(user_agent) = self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                builder
+                    .build()
+                    .map_err(|e| FoxbitError::Config(format!("invalid HTTP client: {}", e)))?
+            }
+        };
+
+        let clock = Arc::new(ServerClock::new(if self.skip_clock_sync {
+            None
+        } else {
+            Some(
+                self.clock_sync_interval
+                    .unwrap_or(DEFAULT_CLOCK_SYNC_INTERVAL),
+            )
+        }));
+        let markets = MarketRegistry::with_clock(
+            http_client.clone(),
+            api_url.clone(),
+            Arc::clone(&clock),
+            self.market_cache_ttl.unwrap_or(DEFAULT_MARKET_CACHE_TTL),
+        );
+        Ok(Foxbit {
+            http_client,
+            api_url,
+            access: self.access,
+            clock,
+            order_validation: if self.skip_order_validation {
+                None
+            } else {
+                Some(self.order_rounding.unwrap_or_default())
+            },
+            markets,
+            page_budget: self.page_budget.unwrap_or_default(),
+        })
+    }
 }
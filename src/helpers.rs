@@ -1,9 +1,12 @@
 use hex;
 use hmac::{Hmac, Mac};
-use serde::Serialize;
+use reqwest::Method;
 use sha2::Sha256;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::error::FoxbitError;
+use crate::types::{Amount, Number};
+
 type HmacSha256 = Hmac<Sha256>;
 
 pub fn create_signature(prehash: &str, api_secret: &str) -> String {
@@ -15,30 +18,52 @@ pub fn create_signature(prehash: &str, api_secret: &str) -> String {
     hex::encode(code_bytes)
 }
 
-pub fn get_timestamp() -> String {
+pub fn get_local_millis() -> u64 {
     match SystemTime::now().duration_since(UNIX_EPOCH) {
-        Ok(n) => n.as_millis().to_string(),
+        Ok(n) => n.as_millis() as u64,
         Err(_) => panic!("SystemTime before UNIX EPOCH"),
     }
 }
 
-pub fn get_prehash<B: Serialize>(
-    endpoint: &str,
+/// Local time in milliseconds, which Foxbit rejects once the local clock
+/// drifts.
+#[deprecated(note = "sign with `ServerClock::now_millis`, which follows the server clock")]
+pub fn get_timestamp() -> String {
+    get_local_millis().to_string()
+}
+
+/// Builds the string signed for a request: timestamp, HTTP method, request
+/// path as sent (e.g. `/rest/v3/orders`), canonical query string and the
+/// exact serialized body.
+pub fn get_prehash(
+    method: &Method,
+    path: &str,
     timestamp: &str,
-    query_string: Option<&str>,
-    body: Option<&B>,
+    query_string: &str,
+    body: &str,
 ) -> String {
-    let method = if body.is_some() { "POST" } else { "GET" };
-
-    let qs = query_string.unwrap_or_else(|| "");
-
-    let b = match body {
-        Some(b) => serde_json::to_string(b).unwrap(),
-        None => "".into(),
-    };
-
     format!(
-        "{}{}{}{}{}{}",
-        timestamp, method, "/rest/v3", endpoint, qs, b
+        "{}{}{}{}{}",
+        timestamp,
+        method.as_str(),
+        path,
+        query_string,
+        body
     )
 }
+
+/// Reads a [`Number`] as an [`Amount`] to calculate with; exact with the
+/// `decimal` feature.
+#[cfg(feature = "decimal")]
+pub(crate) fn to_amount(_name: &str, value: &Number) -> Result<Amount, FoxbitError> {
+    Ok(*value)
+}
+
+/// Reads a [`Number`] as an [`Amount`] to calculate with; exact with the
+/// `decimal` feature.
+#[cfg(not(feature = "decimal"))]
+pub(crate) fn to_amount(name: &str, value: &Number) -> Result<Amount, FoxbitError> {
+    value
+        .parse::<f64>()
+        .map_err(|_| FoxbitError::Validation(format!("{} is not a number: {}", name, value)))
+}
@@ -1,17 +1,45 @@
 pub mod api;
+pub mod clock;
+pub mod credentials;
+pub mod document;
+pub mod error;
+pub mod fees;
 pub mod foxbit;
 pub mod helpers;
+pub mod limits;
+pub mod pagination;
+pub mod portfolio;
+pub mod registry;
 pub mod types;
+pub mod validation;
 
 use dotenv::dotenv;
 use std::env;
 
-pub use foxbit::Foxbit;
+pub use clock::ServerClock;
+pub use credentials::Credentials;
+pub use error::{ApiError, ApiErrorKind, BatchOrderFailure, FoxbitError};
+pub use foxbit::{Authenticated, Foxbit, FoxbitBuilder, Public};
+#[cfg(feature = "decimal")]
+pub use rust_decimal::Decimal;
 
-/// Creates a new instance of Foxbit.
-pub fn new() -> Foxbit {
+/// Creates a new instance of Foxbit from the `FOXBIT_V3_API`, `ACCESS_KEY`
+/// and `API_SECRET` environment variables.
+pub fn new() -> Result<Foxbit, FoxbitError> {
     dotenv().ok();
-    let api_url = env::var("FOXBIT_V3_API").expect("API URL not found");
-    let client: reqwest::Client = reqwest::Client::new();
-    Foxbit::new(client, api_url)
+    let api_url = env::var("FOXBIT_V3_API")
+        .map_err(|_| FoxbitError::Config("FOXBIT_V3_API is not set".into()))?;
+    Foxbit::builder()
+        .api_url(api_url)
+        .credentials(Credentials::from_env()?)
+        .build()
+}
+
+/// Creates a new instance of Foxbit for public market data from the
+/// `FOXBIT_V3_API` environment variable. No API keys are read.
+pub fn new_public() -> Result<Foxbit<Public>, FoxbitError> {
+    dotenv().ok();
+    let api_url = env::var("FOXBIT_V3_API")
+        .map_err(|_| FoxbitError::Config("FOXBIT_V3_API is not set".into()))?;
+    Foxbit::builder().api_url(api_url).build()
 }
@@ -0,0 +1,44 @@
+use std::cmp::Ordering;
+
+use crate::{
+    error::FoxbitError,
+    types::{LimitInterval, LimitOperation, MemberDetails, Number, TransactionalLimit},
+    validation::compare_numbers,
+};
+
+/// A limit that a deposit or withdrawal would go over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitWarning {
+    pub interval: LimitInterval,
+    pub amount: Number,
+    pub remaining: Number,
+}
+
+/// Checks a deposit or withdrawal of `amount` in `currency_symbol` against the
+/// limits of the member's verification level, returning one warning per
+/// interval whose remaining amount it exceeds.
+///
+/// Limits without a level apply to every member.
+pub fn check_limits(
+    limits: &[TransactionalLimit],
+    member: &MemberDetails,
+    currency_symbol: &str,
+    operation: LimitOperation,
+    amount: &Number,
+) -> Result<Vec<LimitWarning>, FoxbitError> {
+    let mut warnings = Vec::new();
+    for limit in limits.iter().filter(|limit| {
+        limit.currency_symbol.eq_ignore_ascii_case(currency_symbol)
+            && limit.r#type == operation
+            && limit.level.is_none_or(|level| level == member.level)
+    }) {
+        if compare_numbers("amount", amount, &limit.remaining)? == Ordering::Greater {
+            warnings.push(LimitWarning {
+                interval: limit.interval.clone(),
+                amount: amount.to_owned(),
+                remaining: limit.remaining.to_owned(),
+            });
+        }
+    }
+    Ok(warnings)
+}
@@ -0,0 +1,112 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::stream::{self, Stream, StreamExt};
+use tokio::time::Instant;
+
+use crate::error::FoxbitError;
+
+/// Page size requested by the streams when the filter does not set one.
+pub const DEFAULT_STREAM_PAGE_SIZE: usize = 100;
+
+/// Limits how fast a pagination stream pulls pages from the API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageBudget {
+    /// Pages requested at the same time. Above one, pages past the last may
+    /// be requested ahead; their results are discarded.
+    pub max_concurrent_pages: usize,
+    /// Minimum time between the start of two page requests.
+    pub min_interval: Duration,
+}
+
+impl Default for PageBudget {
+    fn default() -> Self {
+        PageBudget {
+            max_concurrent_pages: 1,
+            min_interval: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Flattens the pages returned by `fetch` into a stream of items, starting
+/// at `first_page`. Pages are only fetched as the stream is polled. The
+/// stream ends after the first page shorter than `page_size`, or after
+/// yielding the first error.
+pub(crate) fn paginate<'a, T, F, Fut>(
+    first_page: usize,
+    page_size: usize,
+    budget: PageBudget,
+    fetch: F,
+) -> impl Stream<Item = Result<T, FoxbitError>> + 'a
+where
+    T: 'a,
+    F: Fn(usize) -> Fut + 'a,
+    Fut: Future<Output = Result<Vec<T>, FoxbitError>> + 'a,
+{
+    let pacer = Pacer::new(budget.min_interval);
+    // Set once the last page or an error is seen, so that no further pages
+    // are requested and pages fetched ahead are discarded.
+    let done = Arc::new(AtomicBool::new(false));
+    stream::iter(first_page..)
+        .take_while({
+            let done = Arc::clone(&done);
+            move |_| futures::future::ready(!done.load(Ordering::Acquire))
+        })
+        .map(move |page| {
+            let pacer = pacer.clone();
+            let fetch_page = fetch(page);
+            async move {
+                pacer.wait().await;
+                fetch_page.await
+            }
+        })
+        .buffered(budget.max_concurrent_pages.max(1))
+        .scan((), move |_, result| {
+            if done.load(Ordering::Acquire) {
+                return futures::future::ready(None);
+            }
+            let items = match result {
+                Ok(items) => {
+                    if items.len() < page_size {
+                        done.store(true, Ordering::Release);
+                    }
+                    items.into_iter().map(Ok).collect()
+                }
+                Err(e) => {
+                    done.store(true, Ordering::Release);
+                    vec![Err(e)]
+                }
+            };
+            futures::future::ready(Some(stream::iter(items)))
+        })
+        .flatten()
+}
+
+/// Spaces request starts at least `interval` apart, across concurrent pages.
+#[derive(Clone)]
+struct Pacer {
+    interval: Duration,
+    next_start: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Pacer {
+    fn new(interval: Duration) -> Self {
+        Pacer {
+            interval,
+            next_start: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    async fn wait(&self) {
+        let start = {
+            let mut next_start = self.next_start.lock().unwrap();
+            let now = Instant::now();
+            let start = next_start.map_or(now, |next| next.max(now));
+            *next_start = Some(start + self.interval);
+            start
+        };
+        tokio::time::sleep_until(start).await;
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{
+    error::FoxbitError,
+    helpers::to_amount,
+    types::{Account, Amount, Market, Ticker},
+};
+
+/// Value of one balance in the valuation currency.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HoldingValue {
+    pub currency_symbol: String,
+    pub balance: Amount,
+    pub price: Amount,
+    pub value: Amount,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortfolioValuation {
+    pub quote_currency: String,
+    pub total: Amount,
+    pub holdings: Vec<HoldingValue>,
+    /// Currencies with a balance but no market against the quote currency.
+    pub unpriced: Vec<String>,
+}
+
+/// Values `accounts` in `quote_currency` using the last traded price of each
+/// currency's market. A market quoted the other way round (e.g. `brlusdt`
+/// when valuing in USDT) is used inverted. Empty balances are skipped.
+pub fn value_portfolio(
+    accounts: &[Account],
+    markets: &[Market],
+    tickers: &[Ticker],
+    quote_currency: &str,
+) -> Result<PortfolioValuation, FoxbitError> {
+    let mut holdings = Vec::new();
+    let mut unpriced = Vec::new();
+
+    for account in accounts {
+        let balance = to_amount("balance", &account.balance)?;
+        if balance == Amount::from(0) {
+            continue;
+        }
+
+        match find_price(&account.currency_symbol, quote_currency, markets, tickers)? {
+            Some(price) => holdings.push(HoldingValue {
+                currency_symbol: account.currency_symbol.clone(),
+                balance,
+                price,
+                value: balance * price,
+            }),
+            None => unpriced.push(account.currency_symbol.clone()),
+        }
+    }
+
+    Ok(PortfolioValuation {
+        quote_currency: quote_currency.to_string(),
+        total: holdings.iter().map(|holding| holding.value).sum(),
+        holdings,
+        unpriced,
+    })
+}
+
+fn find_price(
+    currency: &str,
+    quote_currency: &str,
+    markets: &[Market],
+    tickers: &[Ticker],
+) -> Result<Option<Amount>, FoxbitError> {
+    if currency.eq_ignore_ascii_case(quote_currency) {
+        return Ok(Some(Amount::from(1)));
+    }
+
+    if let Some(price) = last_price(currency, quote_currency, markets, tickers)? {
+        return Ok(Some(price));
+    }
+    match last_price(quote_currency, currency, markets, tickers)? {
+        Some(price) if price > Amount::from(0) => Ok(Some(Amount::from(1) / price)),
+        _ => Ok(None),
+    }
+}
+
+fn last_price(
+    base: &str,
+    quote: &str,
+    markets: &[Market],
+    tickers: &[Ticker],
+) -> Result<Option<Amount>, FoxbitError> {
+    let is_symbol = |symbol: &Option<String>, expected: &str| {
+        symbol
+            .as_deref()
+            .is_some_and(|symbol| symbol.eq_ignore_ascii_case(expected))
+    };
+
+    let market_symbol = markets
+        .iter()
+        .find(|market| {
+            is_symbol(&market.base.symbol, base) && is_symbol(&market.quote.symbol, quote)
+        })
+        .and_then(|market| market.symbol.as_deref());
+    let Some(market_symbol) = market_symbol else {
+        return Ok(None);
+    };
+
+    tickers
+        .iter()
+        .find(|ticker| ticker.market_symbol.eq_ignore_ascii_case(market_symbol))
+        .map(|ticker| to_amount("last price", &ticker.last_trade.price))
+        .transpose()
+}
@@ -0,0 +1,231 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use reqwest::Client;
+use tokio::sync::Mutex;
+
+use crate::{
+    api::Api,
+    clock::ServerClock,
+    error::FoxbitError,
+    types::{Currency, Market, Number},
+    validation::{round_price, round_quantity, RoundingMode},
+};
+
+/// How long market and currency metadata is cached by default.
+pub const DEFAULT_MARKET_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// Caches the markets and currencies listed by Foxbit, which change rarely
+/// but are needed to validate and format every order.
+///
+/// The first lookup loads both lists. Once the TTL has elapsed, lookups keep
+/// returning the cached lists while a refresh runs in the background.
+/// Clones share the same cache.
+#[derive(Clone)]
+pub struct MarketRegistry {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    http_client: Client,
+    api_url: String,
+    /// Never read, as only public endpoints are called; shared with the
+    /// client that owns the registry, if any.
+    clock: Arc<ServerClock>,
+    ttl: Duration,
+    snapshot: RwLock<Option<Arc<MarketSnapshot>>>,
+    loading: Mutex<()>,
+    refreshing: AtomicBool,
+}
+
+impl MarketRegistry {
+    pub fn new(http_client: Client, api_url: String, ttl: Duration) -> Self {
+        MarketRegistry::with_clock(http_client, api_url, Arc::new(ServerClock::new(None)), ttl)
+    }
+
+    /// Like [`new`](Self::new), but sharing the clock of the client that owns
+    /// the registry.
+    pub(crate) fn with_clock(
+        http_client: Client,
+        api_url: String,
+        clock: Arc<ServerClock>,
+        ttl: Duration,
+    ) -> Self {
+        MarketRegistry {
+            inner: Arc::new(Inner {
+                http_client,
+                api_url,
+                clock,
+                ttl,
+                snapshot: RwLock::new(None),
+                loading: Mutex::new(()),
+                refreshing: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.inner.ttl
+    }
+
+    /// Returns the cached markets and currencies, loading them on first use
+    /// and refreshing them in the background once they are older than the
+    /// TTL.
+    pub async fn snapshot(&self) -> Result<Arc<MarketSnapshot>, FoxbitError> {
+        if let Some(snapshot) = self.cached() {
+            if snapshot.loaded_at.elapsed() >= self.inner.ttl {
+                self.refresh_in_background();
+            }
+            return Ok(snapshot);
+        }
+
+        let _loading = self.inner.loading.lock().await;
+        if let Some(snapshot) = self.cached() {
+            return Ok(snapshot);
+        }
+        self.inner.load().await
+    }
+
+    /// Reloads markets and currencies now, replacing the cache.
+    pub async fn refresh(&self) -> Result<Arc<MarketSnapshot>, FoxbitError> {
+        let _loading = self.inner.loading.lock().await;
+        self.inner.load().await
+    }
+
+    fn cached(&self) -> Option<Arc<MarketSnapshot>> {
+        self.inner.snapshot.read().unwrap().clone()
+    }
+
+    fn refresh_in_background(&self) {
+        if self.inner.refreshing.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let inner = Arc::clone(&self.inner);
+        tokio::spawn(async move {
+            let _loading = inner.loading.lock().await;
+            // A failed refresh keeps the stale lists; the next lookup retries.
+            let _ = inner.load().await;
+            inner.refreshing.store(false, Ordering::Release);
+        });
+    }
+}
+
+impl Inner {
+    async fn load(&self) -> Result<Arc<MarketSnapshot>, FoxbitError> {
+        let api = Api::new(&self.http_client, &self.api_url, None, &self.clock);
+        let (markets, currencies) = tokio::try_join!(api.list_markets(), api.list_currencies())?;
+        let snapshot = Arc::new(MarketSnapshot {
+            markets,
+            currencies,
+            loaded_at: Instant::now(),
+        });
+        *self.snapshot.write().unwrap() = Some(Arc::clone(&snapshot));
+        Ok(snapshot)
+    }
+}
+
+/// Markets and currencies as loaded at one point in time.
+#[derive(Debug)]
+pub struct MarketSnapshot {
+    markets: Vec<Market>,
+    currencies: Vec<Currency>,
+    loaded_at: Instant,
+}
+
+impl MarketSnapshot {
+    pub fn markets(&self) -> &[Market] {
+        &self.markets
+    }
+
+    pub fn currencies(&self) -> &[Currency] {
+        &self.currencies
+    }
+
+    pub fn loaded_at(&self) -> Instant {
+        self.loaded_at
+    }
+
+    /// Finds a market by symbol, e.g. `btcbrl`, ignoring case.
+    pub fn market(&self, symbol: &str) -> Option<&Market> {
+        self.markets
+            .iter()
+            .find(|market| matches_symbol(market.symbol.as_deref(), symbol))
+    }
+
+    /// Finds the market trading `base` against `quote`, ignoring case.
+    pub fn market_for_pair(&self, base: &str, quote: &str) -> Option<&Market> {
+        self.markets.iter().find(|market| {
+            matches_symbol(market.base.symbol.as_deref(), base)
+                && matches_symbol(market.quote.symbol.as_deref(), quote)
+        })
+    }
+
+    /// Finds a currency by symbol, e.g. `btc`, ignoring case.
+    pub fn currency(&self, symbol: &str) -> Option<&Currency> {
+        self.currencies
+            .iter()
+            .find(|currency| matches_symbol(currency.symbol.as_deref(), symbol))
+    }
+
+    /// Currencies whose category has the code `category`, ignoring case.
+    pub fn currencies_in_category(&self, category: &str) -> Vec<&Currency> {
+        self.currencies
+            .iter()
+            .filter(|currency| in_category(currency, category))
+            .collect()
+    }
+
+    /// Markets whose base currency is in the category `category`.
+    pub fn markets_in_category(&self, category: &str) -> Vec<&Market> {
+        self.markets
+            .iter()
+            .filter(|market| in_category(&market.base, category))
+            .collect()
+    }
+
+    /// Snaps `price` to the price increment of `market_symbol`. See
+    /// [`round_price`].
+    pub fn round_price(
+        &self,
+        market_symbol: &str,
+        price: &Number,
+        rounding: RoundingMode,
+    ) -> Result<Number, FoxbitError> {
+        round_price(self.require_market(market_symbol)?, price, rounding)
+    }
+
+    /// Snaps `quantity` to the quantity increment of `market_symbol`. See
+    /// [`round_quantity`].
+    pub fn round_quantity(
+        &self,
+        market_symbol: &str,
+        quantity: &Number,
+        rounding: RoundingMode,
+    ) -> Result<Number, FoxbitError> {
+        round_quantity(self.require_market(market_symbol)?, quantity, rounding)
+    }
+
+    /// Like [`market`](Self::market), but unknown markets are an error.
+    pub fn require_market(&self, symbol: &str) -> Result<&Market, FoxbitError> {
+        self.market(symbol)
+            .ok_or_else(|| FoxbitError::Validation(format!("Unknown market: {}", symbol)))
+    }
+
+    /// Like [`currency`](Self::currency), but unknown currencies are an error.
+    pub fn require_currency(&self, symbol: &str) -> Result<&Currency, FoxbitError> {
+        self.currency(symbol)
+            .ok_or_else(|| FoxbitError::Validation(format!("Unknown currency: {}", symbol)))
+    }
+}
+
+fn matches_symbol(symbol: Option<&str>, wanted: &str) -> bool {
+    symbol.is_some_and(|symbol| symbol.eq_ignore_ascii_case(wanted))
+}
+
+fn in_category(currency: &Currency, category: &str) -> bool {
+    currency
+        .category
+        .as_ref()
+        .is_some_and(|c| matches_symbol(c.code.as_deref(), category))
+}
@@ -1,4 +1,159 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::document::normalize_document;
+use crate::error::{BatchOrderFailure, FoxbitError};
+use crate::validation::compare_numbers;
+
+/// Price, quantity or amount as sent by Foxbit. With the `decimal` feature it
+/// is a [`Decimal`](rust_decimal::Decimal), read from and written back to
+/// Foxbit's strings without loss; otherwise it is the string itself.
+#[cfg(feature = "decimal")]
+pub type Number = rust_decimal::Decimal;
+#[cfg(not(feature = "decimal"))]
+pub type Number = String;
+
+/// Result of calculations on [`Number`]s, such as fee estimates and portfolio
+/// values. With the `decimal` feature it is a
+/// [`Decimal`](rust_decimal::Decimal), so no precision is lost; otherwise it
+/// is an `f64`.
+#[cfg(feature = "decimal")]
+pub type Amount = rust_decimal::Decimal;
+#[cfg(not(feature = "decimal"))]
+pub type Amount = f64;
+
+/// Defines a string enum whose values the server may extend. Values without a
+/// variant are kept in `Unknown` instead of failing deserialization.
+macro_rules! string_enum {
+    ($(#[$meta:meta])* $name:ident { $($variant:ident => $value:literal,)+ }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)+
+            Unknown(String),
+        }
+
+        impl $name {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)+
+                    $name::Unknown(value) => value,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            /// Exact matches win; otherwise the value is matched ignoring case,
+            /// as some endpoints use lowercase values.
+            fn from(value: &str) -> Self {
+                $(if value == $value {
+                    return $name::$variant;
+                })+
+                $(if value.eq_ignore_ascii_case($value) {
+                    return $name::$variant;
+                })+
+                $name::Unknown(value.to_string())
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                Ok($name::from(value.as_str()))
+            }
+        }
+    };
+}
+
+string_enum! {
+    Side {
+        Buy => "BUY",
+        Sell => "SELL",
+    }
+}
+
+string_enum! {
+    OrderType {
+        Limit => "LIMIT",
+        Market => "MARKET",
+        StopLimit => "STOP_LIMIT",
+        StopMarket => "STOP_MARKET",
+        Instant => "INSTANT",
+    }
+}
+
+string_enum! {
+    OrderState {
+        Active => "ACTIVE",
+        PartiallyFilled => "PARTIALLY_FILLED",
+        Filled => "FILLED",
+        PartiallyCanceled => "PARTIALLY_CANCELED",
+        Canceled => "CANCELED",
+    }
+}
+
+string_enum! {
+    TimeInForce {
+        GoodTillCancelled => "GTC",
+        ImmediateOrCancel => "IOC",
+        FillOrKill => "FOK",
+    }
+}
+
+string_enum! {
+    CandleInterval {
+        OneMinute => "1m",
+        FiveMinutes => "5m",
+        FifteenMinutes => "15m",
+        ThirtyMinutes => "30m",
+        OneHour => "1h",
+        TwoHours => "2h",
+        FourHours => "4h",
+        SixHours => "6h",
+        TwelveHours => "12h",
+        OneDay => "1d",
+        OneWeek => "1w",
+        TwoWeeks => "2w",
+        OneMonth => "1M",
+    }
+}
+
+string_enum! {
+    /// Whether a trade's order was resting on the book or took liquidity.
+    TradeRole {
+        Maker => "MAKER",
+        Taker => "TAKER",
+    }
+}
+
+string_enum! {
+    /// Operation a transactional limit applies to.
+    LimitOperation {
+        Deposit => "DEPOSIT",
+        Withdrawal => "WITHDRAWAL",
+    }
+}
+
+string_enum! {
+    /// Period over which a transactional limit is counted.
+    LimitInterval {
+        Daily => "DAILY",
+        Monthly => "MONTHLY",
+    }
+}
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Category {
@@ -9,14 +164,49 @@ pub struct Category {
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct WithdrawInfo {
     pub enabled: Option<bool>,
-    pub min_amount: Option<String>,
-    pub fee: Option<String>,
+    pub min_amount: Option<Number>,
+    pub fee: Option<Number>,
+}
+
+impl WithdrawInfo {
+    /// Checks `amount` against the currency's withdrawal rules, so that a
+    /// withdrawal Foxbit would reject is not sent.
+    pub fn check_amount(&self, amount: &Number) -> Result<(), FoxbitError> {
+        if self.enabled == Some(false) {
+            return Err(FoxbitError::Validation(
+                "Withdrawals are disabled for this currency".into(),
+            ));
+        }
+        if compare_numbers("amount", amount, "0")? != Ordering::Greater {
+            return Err(FoxbitError::Validation(format!(
+                "amount must be positive, got {}",
+                amount
+            )));
+        }
+        if let Some(min_amount) = &self.min_amount {
+            if compare_numbers("amount", amount, min_amount)? == Ordering::Less {
+                return Err(FoxbitError::Validation(format!(
+                    "amount {} is below the minimum withdrawal of {}",
+                    amount, min_amount
+                )));
+            }
+        }
+        if let Some(fee) = &self.fee {
+            if compare_numbers("amount", amount, fee)? != Ordering::Greater {
+                return Err(FoxbitError::Validation(format!(
+                    "amount {} does not cover the withdrawal fee of {}",
+                    amount, fee
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct DepositInfo {
     pub min_to_confirm: Option<String>,
-    pub min_amount: Option<String>,
+    pub min_amount: Option<Number>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -33,29 +223,29 @@ pub struct Currency {
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Market {
     pub symbol: Option<String>,
-    pub quantity_min: Option<String>,
-    pub quantity_increment: Option<String>,
-    pub price_min: Option<String>,
-    pub price_increment: Option<String>,
+    pub quantity_min: Option<Number>,
+    pub quantity_increment: Option<Number>,
+    pub price_min: Option<Number>,
+    pub price_increment: Option<Number>,
     pub base: Currency,
     pub quote: Currency,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Quote {
-    pub side: Option<String>,
+    pub side: Option<Side>,
     pub market_symbol: Option<String>,
-    pub base_amount: Option<String>,
-    pub quote_amount: Option<String>,
-    pub price: Option<String>,
+    pub base_amount: Option<Number>,
+    pub quote_amount: Option<Number>,
+    pub price: Option<Number>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct OrderBook {
     pub sequence_id: u32,
     pub timestamp: u64,
-    pub bids: Vec<Vec<String>>,
-    pub asks: Vec<Vec<String>>,
+    pub bids: Vec<Vec<Number>>,
+    pub asks: Vec<Vec<Number>>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -66,16 +256,63 @@ pub struct FoxBitResponse<T> {
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Candlestick {
     pub open_time: String,
-    pub open_price: String,
-    pub high_price: String,
-    pub low_price: String,
-    pub close_price: String,
+    pub open_price: Number,
+    pub high_price: Number,
+    pub low_price: Number,
+    pub close_price: Number,
     pub close_time: String,
-    pub volume: String,
-    pub quote_asset_volume: String,
+    pub volume: Number,
+    pub quote_asset_volume: Number,
     pub number_of_trades: i32,
-    pub taker_buy_base_asset_volume: String,
-    pub taker_buy_quote_asset_volume: String,
+    pub taker_buy_base_asset_volume: Number,
+    pub taker_buy_quote_asset_volume: Number,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TickerTrade {
+    pub price: Number,
+    pub volume: Number,
+    pub date: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TickerRolling24h {
+    pub price_change: Number,
+    pub price_change_percent: Number,
+    pub volume: Number,
+    pub trades_count: u64,
+    pub open: Number,
+    pub high: Number,
+    pub low: Number,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct PriceLevel {
+    pub price: Number,
+    pub volume: Number,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TickerBest {
+    pub ask: Option<PriceLevel>,
+    pub bid: Option<PriceLevel>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Ticker {
+    pub market_symbol: String,
+    pub last_trade: TickerTrade,
+    pub rolling_24h: TickerRolling24h,
+    pub best: TickerBest,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicTrade {
+    pub id: u64,
+    pub price: Number,
+    pub volume: Number,
+    pub taker_side: Side,
+    pub created_at: String,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -100,6 +337,129 @@ pub struct MemberDetails {
     pub disabled: bool,
 }
 
+/// Deposit or withdrawal limit of one currency over a daily or monthly
+/// interval.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TransactionalLimit {
+    pub currency_symbol: String,
+    pub r#type: LimitOperation,
+    pub interval: LimitInterval,
+    /// Member level the limit applies to.
+    pub level: Option<usize>,
+    pub amount: Number,
+    pub used: Number,
+    pub remaining: Number,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct TradingFee {
+    pub market_symbol: String,
+    pub maker: Number,
+    pub taker: Number,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Account {
+    pub currency_symbol: String,
+    pub balance: Number,
+    pub balance_available: Number,
+    pub balance_locked: Number,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Network {
+    pub name: String,
+    pub code: String,
+}
+
+/// Filters for [`Foxbit::orders_stream`](crate::foxbit::Foxbit::orders_stream)
+/// and a single page of `/orders`. Unset fields are not sent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OrderFilter {
+    pub market_symbol: Option<String>,
+    pub state: Option<OrderState>,
+    pub side: Option<Side>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub page_size: Option<usize>,
+    pub page: Option<usize>,
+}
+
+/// Filters for [`Foxbit::trades_stream`](crate::foxbit::Foxbit::trades_stream)
+/// and a single page of `/trades`. Unset fields are not sent.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TradeFilter {
+    pub market_symbol: Option<String>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub page_size: Option<usize>,
+    pub page: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DepositFilter {
+    pub state: Option<String>,
+    pub currency_symbol: Option<String>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub page_size: Option<usize>,
+    pub page: Option<usize>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct DepositCryptoDetails {
+    pub network: Option<Network>,
+    pub transaction_id: Option<String>,
+    pub receiving_address: Option<String>,
+    pub destination_tag: Option<String>,
+    pub confirmations: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Deposit {
+    pub sn: String,
+    pub state: String,
+    pub currency_symbol: String,
+    pub amount: Number,
+    pub fee: Option<Number>,
+    pub created_at: String,
+    pub details_crypto: Option<DepositCryptoDetails>,
+}
+
+impl Deposit {
+    /// Confirmations still needed before the deposit is credited, according
+    /// to the currency's `min_to_confirm`. `None` when either side is unknown,
+    /// e.g. for fiat deposits.
+    pub fn confirmations_remaining(&self, deposit_info: &DepositInfo) -> Option<u64> {
+        let confirmations = self.details_crypto.as_ref()?.confirmations?;
+        let min_to_confirm = deposit_info.min_to_confirm.as_ref()?.parse::<u64>().ok()?;
+        Some(min_to_confirm.saturating_sub(confirmations))
+    }
+
+    pub fn is_confirmed(&self, deposit_info: &DepositInfo) -> Option<bool> {
+        self.confirmations_remaining(deposit_info)
+            .map(|remaining| remaining == 0)
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct DepositAddress {
+    pub currency_symbol: String,
+    pub address: String,
+    /// Memo or message that must accompany deposits of some currencies.
+    pub message: Option<String>,
+    /// Destination tag that must accompany deposits of some currencies.
+    pub destination_tag: Option<String>,
+    pub network: Option<Network>,
+}
+
+impl DepositAddress {
+    /// Returns `true` when deposits must carry a memo or destination tag.
+    pub fn requires_memo(&self) -> bool {
+        self.message.is_some() || self.destination_tag.is_some()
+    }
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct CreateOrderResponse {
     pub id: u64,
@@ -107,27 +467,317 @@ pub struct CreateOrderResponse {
     pub client_order_id: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderRequest {
+    pub side: Side,
+    pub r#type: OrderType,
+    pub market_symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<Number>,
+    /// Quote currency amount to spend, for instant orders.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_price: Option<Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_in_force: Option<TimeInForce>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_order_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remark: Option<String>,
+}
+
+impl OrderRequest {
+    pub fn new(
+        side: Side,
+        r#type: OrderType,
+        market_symbol: &str,
+        quantity: impl Into<Number>,
+    ) -> Self {
+        OrderRequest {
+            side,
+            r#type,
+            market_symbol: market_symbol.to_string(),
+            quantity: Some(quantity.into()),
+            amount: None,
+            price: None,
+            stop_price: None,
+            time_in_force: None,
+            post_only: None,
+            client_order_id: None,
+            remark: None,
+        }
+    }
+
+    /// Starts an order of `type` on `market_symbol`. The fields each type
+    /// needs are checked by [`OrderRequestBuilder::build`].
+    pub fn builder(side: Side, r#type: OrderType, market_symbol: &str) -> OrderRequestBuilder {
+        OrderRequestBuilder {
+            request: OrderRequest {
+                side,
+                r#type,
+                market_symbol: market_symbol.to_string(),
+                quantity: None,
+                amount: None,
+                price: None,
+                stop_price: None,
+                time_in_force: None,
+                post_only: None,
+                client_order_id: None,
+                remark: None,
+            },
+        }
+    }
+
+    pub fn limit(side: Side, market_symbol: &str) -> OrderRequestBuilder {
+        OrderRequest::builder(side, OrderType::Limit, market_symbol)
+    }
+
+    pub fn market(side: Side, market_symbol: &str) -> OrderRequestBuilder {
+        OrderRequest::builder(side, OrderType::Market, market_symbol)
+    }
+
+    pub fn stop_limit(side: Side, market_symbol: &str) -> OrderRequestBuilder {
+        OrderRequest::builder(side, OrderType::StopLimit, market_symbol)
+    }
+
+    pub fn instant(side: Side, market_symbol: &str) -> OrderRequestBuilder {
+        OrderRequest::builder(side, OrderType::Instant, market_symbol)
+    }
+}
+
+/// Builds an [`OrderRequest`], rejecting fields that do not apply to its
+/// order type.
+#[derive(Debug, Clone)]
+pub struct OrderRequestBuilder {
+    request: OrderRequest,
+}
+
+impl OrderRequestBuilder {
+    pub fn quantity(mut self, quantity: impl Into<Number>) -> Self {
+        self.request.quantity = Some(quantity.into());
+        self
+    }
+
+    pub fn amount(mut self, amount: impl Into<Number>) -> Self {
+        self.request.amount = Some(amount.into());
+        self
+    }
+
+    pub fn price(mut self, price: impl Into<Number>) -> Self {
+        self.request.price = Some(price.into());
+        self
+    }
+
+    pub fn stop_price(mut self, stop_price: impl Into<Number>) -> Self {
+        self.request.stop_price = Some(stop_price.into());
+        self
+    }
+
+    pub fn time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.request.time_in_force = Some(time_in_force);
+        self
+    }
+
+    /// Rejects the order instead of letting it take liquidity.
+    pub fn post_only(mut self) -> Self {
+        self.request.post_only = Some(true);
+        self
+    }
+
+    pub fn client_order_id(mut self, client_order_id: impl Into<String>) -> Self {
+        self.request.client_order_id = Some(client_order_id.into());
+        self
+    }
+
+    pub fn remark(mut self, remark: impl Into<String>) -> Self {
+        self.request.remark = Some(remark.into());
+        self
+    }
+
+    /// Checks the fields against the order type:
+    ///
+    /// | type          | requires                 | accepts                  |
+    /// |---------------|--------------------------|--------------------------|
+    /// | `LIMIT`       | quantity, price          | time in force, post-only |
+    /// | `MARKET`      | quantity                 |                          |
+    /// | `STOP_LIMIT`  | quantity, price, stop    | time in force            |
+    /// | `STOP_MARKET` | quantity, stop           |                          |
+    /// | `INSTANT`     | amount                   |                          |
+    ///
+    /// Client order id and remark are accepted on every type. Order types
+    /// the crate does not know are passed through unchecked.
+    pub fn build(self) -> Result<OrderRequest, FoxbitError> {
+        let request = self.request;
+        let (required, accepted): (&[&str], &[&str]) = match request.r#type {
+            OrderType::Limit => (&["quantity", "price"], &["time_in_force", "post_only"]),
+            OrderType::Market => (&["quantity"], &[]),
+            OrderType::StopLimit => (&["quantity", "price", "stop_price"], &["time_in_force"]),
+            OrderType::StopMarket => (&["quantity", "stop_price"], &[]),
+            OrderType::Instant => (&["amount"], &[]),
+            OrderType::Unknown(_) => return Ok(request),
+        };
+
+        let fields = [
+            ("quantity", request.quantity.is_some()),
+            ("amount", request.amount.is_some()),
+            ("price", request.price.is_some()),
+            ("stop_price", request.stop_price.is_some()),
+            ("time_in_force", request.time_in_force.is_some()),
+            ("post_only", request.post_only.is_some()),
+        ];
+        for (field, set) in fields {
+            if !set && required.contains(&field) {
+                return Err(FoxbitError::Validation(format!(
+                    "{} orders require {}",
+                    request.r#type, field
+                )));
+            }
+            if set && !required.contains(&field) && !accepted.contains(&field) {
+                return Err(FoxbitError::Validation(format!(
+                    "{} orders do not accept {}",
+                    request.r#type, field
+                )));
+            }
+        }
+        if request.post_only == Some(true)
+            && matches!(
+                request.time_in_force,
+                Some(TimeInForce::ImmediateOrCancel | TimeInForce::FillOrKill)
+            )
+        {
+            return Err(FoxbitError::Validation(
+                "post-only orders must rest on the book and cannot be IOC or FOK".into(),
+            ));
+        }
+        Ok(request)
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct BatchOrderRequest<'a> {
+    pub validate_only: bool,
+    pub requests: &'a [OrderRequest],
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct BatchOrderError {
+    pub code: Option<i64>,
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct BatchOrderResponse {
+    pub id: Option<u64>,
+    pub sn: Option<String>,
+    pub client_order_id: Option<String>,
+    pub error: Option<BatchOrderError>,
+}
+
+/// Outcome of one order of a batch, in the same position as its request.
+#[derive(Debug)]
+pub struct BatchOrderResult {
+    pub client_order_id: Option<String>,
+    pub result: Result<CreateOrderResponse, BatchOrderFailure>,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct CancelOrderResponse {
     pub id: u64,
     pub sn: String,
 }
 
+/// Identifies an existing order by its id or by its client order id.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderRef {
+    Id { id: u64 },
+    ClientOrderId { client_order_id: String },
+}
+
+impl OrderRef {
+    pub fn id(id: u64) -> Self {
+        OrderRef::Id { id }
+    }
+
+    pub fn client_order_id(client_order_id: &str) -> Self {
+        OrderRef::ClientOrderId {
+            client_order_id: client_order_id.to_string(),
+        }
+    }
+}
+
+/// Selects the orders cancelled by `cancel_orders`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CancelMode {
+    Id { id: u64 },
+    ClientOrderId { client_order_id: String },
+    Market { market_symbol: String },
+    All,
+}
+
+impl CancelMode {
+    pub fn id(id: u64) -> Self {
+        CancelMode::Id { id }
+    }
+
+    pub fn client_order_id(client_order_id: &str) -> Self {
+        CancelMode::ClientOrderId {
+            client_order_id: client_order_id.to_string(),
+        }
+    }
+
+    pub fn market(market_symbol: &str) -> Self {
+        CancelMode::Market {
+            market_symbol: market_symbol.to_string(),
+        }
+    }
+}
+
+impl From<OrderRef> for CancelMode {
+    fn from(order: OrderRef) -> Self {
+        match order {
+            OrderRef::Id { id } => CancelMode::Id { id },
+            OrderRef::ClientOrderId { client_order_id } => {
+                CancelMode::ClientOrderId { client_order_id }
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CancelReplaceRequest<'a> {
+    pub mode: &'a str,
+    pub cancel: &'a OrderRef,
+    pub create: &'a OrderRequest,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct CancelReplaceResponse {
+    pub cancel: CancelOrderResponse,
+    pub create: CreateOrderResponse,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Order {
     pub id: String,
     pub sn: String,
     pub market_symbol: String,
     pub client_order_id: Option<String>,
-    pub side: String,
-    pub r#type: String,
-    pub state: String,
-    pub price: Option<String>,
-    pub price_avg: String,
-    pub quantity: String,
-    pub quantity_executed: String,
-    pub instant_amount: Option<String>,
-    pub instant_amount_executed: Option<String>,
+    pub side: Side,
+    pub r#type: OrderType,
+    pub state: OrderState,
+    pub price: Option<Number>,
+    pub price_avg: Number,
+    pub quantity: Number,
+    pub quantity_executed: Number,
+    pub instant_amount: Option<Number>,
+    pub instant_amount_executed: Option<Number>,
     pub created_at: String,
     pub trades_count: i32,
     pub cancellation_reason: Option<String>,
@@ -139,11 +789,186 @@ pub struct Trade {
     pub sn: String,
     pub order_id: String,
     pub market_symbol: String,
-    pub side: String,
-    pub price: String,
-    pub quantity: String,
-    pub fee: String,
+    pub side: Side,
+    pub price: Number,
+    pub quantity: Number,
+    pub fee: Number,
     pub fee_currency_symbol: String,
     pub created_at: String,
-    pub role: String,
+    pub role: TradeRole,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WithdrawalFilter {
+    pub state: Option<String>,
+    pub currency_symbol: Option<String>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub page_size: Option<usize>,
+    pub page: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WithdrawalCryptoDestination {
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_tag: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BankAccountType {
+    Checking,
+    Savings,
+    Payment,
+}
+
+/// Brazilian bank account receiving a BRL withdrawal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BankAccount {
+    /// COMPE code of the bank, as listed by `list_banks`.
+    pub bank_code: usize,
+    pub branch: String,
+    pub account: String,
+    pub account_type: BankAccountType,
+    /// CPF or CNPJ of the account holder, digits only.
+    pub holder_document: String,
+}
+
+impl BankAccount {
+    pub fn new(
+        bank_code: usize,
+        branch: &str,
+        account: &str,
+        account_type: BankAccountType,
+        holder_document: &str,
+    ) -> Self {
+        BankAccount {
+            bank_code,
+            branch: branch.to_string(),
+            account: account.to_string(),
+            account_type,
+            holder_document: normalize_document(holder_document),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WithdrawalRequest {
+    pub currency_symbol: String,
+    pub amount: Number,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_crypto: Option<WithdrawalCryptoDestination>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination_bank_account: Option<BankAccount>,
+}
+
+impl WithdrawalRequest {
+    /// Withdrawal of `amount` to a crypto `address`, on the currency's default
+    /// network unless `network_code` is set on the destination.
+    pub fn crypto(currency_symbol: &str, amount: impl Into<Number>, address: &str) -> Self {
+        WithdrawalRequest {
+            currency_symbol: currency_symbol.to_string(),
+            amount: amount.into(),
+            destination_crypto: Some(WithdrawalCryptoDestination {
+                address: address.to_string(),
+                network_code: None,
+                destination_tag: None,
+            }),
+            destination_bank_account: None,
+        }
+    }
+
+    /// BRL withdrawal of `amount` to a Brazilian bank account.
+    pub fn fiat(amount: impl Into<Number>, bank_account: BankAccount) -> Self {
+        WithdrawalRequest {
+            currency_symbol: "brl".to_string(),
+            amount: amount.into(),
+            destination_crypto: None,
+            destination_bank_account: Some(bank_account),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct WithdrawalCryptoDetails {
+    pub network: Option<Network>,
+    pub transaction_id: Option<String>,
+    pub destination_address: Option<String>,
+    pub destination_tag: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Withdrawal {
+    pub sn: String,
+    pub state: String,
+    pub currency_symbol: String,
+    pub amount: Number,
+    pub fee: Option<Number>,
+    pub created_at: String,
+    pub details_crypto: Option<WithdrawalCryptoDetails>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct OtcMarket {
+    pub market_symbol: String,
+    pub base_currency_symbol: String,
+    pub quote_currency_symbol: String,
+    pub quantity_min: Option<Number>,
+    pub quantity_max: Option<Number>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OtcQuoteRequest {
+    pub side: Side,
+    pub market_symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Number>,
+}
+
+/// Firm price offered by the OTC desk, valid until `expires_at`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OtcQuote {
+    pub quote_id: String,
+    pub side: Side,
+    pub market_symbol: String,
+    pub price: Number,
+    pub quantity: Number,
+    pub amount: Number,
+    pub created_at: String,
+    /// Expiry of the quote, in milliseconds since the UNIX epoch.
+    pub expires_at: u64,
+}
+
+impl OtcQuote {
+    /// Returns `true` when the quote has expired at `now_millis`, a server
+    /// time such as [`ServerClock::now_millis`](crate::ServerClock::now_millis).
+    pub fn is_expired_at(&self, now_millis: u64) -> bool {
+        now_millis >= self.expires_at
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct OtcExecution {
+    pub sn: String,
+    pub quote_id: String,
+    pub side: Side,
+    pub market_symbol: String,
+    pub price: Number,
+    pub quantity: Number,
+    pub amount: Number,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OtcExecutionFilter {
+    pub market_symbol: Option<String>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub page_size: Option<usize>,
+    pub page: Option<usize>,
 }
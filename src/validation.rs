@@ -0,0 +1,251 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+
+use crate::{
+    error::FoxbitError,
+    types::{Currency, Market, Number, OrderRequest},
+};
+
+/// How prices and quantities that fall between two increments are snapped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Towards zero, so an order never uses more than was asked for.
+    #[default]
+    Down,
+    /// Away from zero.
+    Up,
+    /// To the nearest increment, halves away from zero.
+    Nearest,
+    /// Rejects values that are not already a multiple of the increment.
+    Strict,
+}
+
+/// Checks `order` against the trading rules of `market` and returns it with
+/// its numbers snapped to the market's increments and formatted with their
+/// precision.
+///
+/// Quantities follow `quantity_increment`, prices and stop prices follow
+/// `price_increment`, and instant order amounts follow the precision of the
+/// quote currency. A missing increment falls back to the currency precision.
+/// Orders below `quantity_min` or `price_min` once snapped are rejected.
+pub fn normalize_order(
+    order: &OrderRequest,
+    market: &Market,
+    rounding: RoundingMode,
+) -> Result<OrderRequest, FoxbitError> {
+    let mut normalized = order.clone();
+    let quantity_step = step(market.quantity_increment.as_ref(), &market.base)?;
+    let price_step = step(market.price_increment.as_ref(), &market.quote)?;
+    let amount_step = step(None, &market.quote)?;
+
+    if let Some(quantity) = &order.quantity {
+        let snapped = snap("quantity", quantity, quantity_step, rounding)?;
+        check_min("quantity", &snapped, market.quantity_min.as_ref())?;
+        normalized.quantity = Some(snapped.to_number()?);
+    }
+    if let Some(amount) = &order.amount {
+        let snapped = snap("amount", amount, amount_step, rounding)?;
+        check_min("amount", &snapped, None)?;
+        normalized.amount = Some(snapped.to_number()?);
+    }
+    if let Some(price) = &order.price {
+        let snapped = snap("price", price, price_step, rounding)?;
+        check_min("price", &snapped, market.price_min.as_ref())?;
+        normalized.price = Some(snapped.to_number()?);
+    }
+    if let Some(stop_price) = &order.stop_price {
+        let snapped = snap("stop_price", stop_price, price_step, rounding)?;
+        check_min("stop_price", &snapped, market.price_min.as_ref())?;
+        normalized.stop_price = Some(snapped.to_number()?);
+    }
+    Ok(normalized)
+}
+
+/// Snaps `price` to the price increment of `market` and formats it with the
+/// increment's precision, e.g. for display or to build an order by hand.
+pub fn round_price(
+    market: &Market,
+    price: &Number,
+    rounding: RoundingMode,
+) -> Result<Number, FoxbitError> {
+    let price_step = step(market.price_increment.as_ref(), &market.quote)?;
+    snap("price", price, price_step, rounding)?.to_number()
+}
+
+/// Snaps `quantity` to the quantity increment of `market` and formats it
+/// with the increment's precision.
+pub fn round_quantity(
+    market: &Market,
+    quantity: &Number,
+    rounding: RoundingMode,
+) -> Result<Number, FoxbitError> {
+    let quantity_step = step(market.quantity_increment.as_ref(), &market.base)?;
+    snap("quantity", quantity, quantity_step, rounding)?.to_number()
+}
+
+/// Compares two numbers exactly, without going through floating point.
+pub(crate) fn compare_numbers(
+    name: &str,
+    value: &(impl Display + ?Sized),
+    other: &(impl Display + ?Sized),
+) -> Result<Ordering, FoxbitError> {
+    let value = Fixed::parse(name, &value.to_string())?;
+    let other = Fixed::parse(name, &other.to_string())?;
+    value
+        .compare(&other)
+        .ok_or_else(|| FoxbitError::Validation(format!("{} is out of range", name)))
+}
+
+/// Subtracts `other` from `value` exactly, without going through floating
+/// point.
+pub(crate) fn subtract_numbers(
+    name: &str,
+    value: &(impl Display + ?Sized),
+    other: &(impl Display + ?Sized),
+) -> Result<Number, FoxbitError> {
+    let value = Fixed::parse(name, &value.to_string())?;
+    let other = Fixed::parse(name, &other.to_string())?;
+    let scale = value.scale.max(other.scale);
+    let mantissa = value
+        .rescale(scale)
+        .zip(other.rescale(scale))
+        .and_then(|(value, other)| value.checked_sub(other))
+        .ok_or_else(|| FoxbitError::Validation(format!("{} is out of range", name)))?;
+    Fixed { mantissa, scale }.to_number()
+}
+
+/// Exact decimal number as `mantissa * 10^-scale`, so that snapping does
+/// not go through floating point.
+#[derive(Debug, Clone, Copy)]
+struct Fixed {
+    mantissa: i128,
+    scale: u32,
+}
+
+impl Fixed {
+    fn parse(name: &str, value: &str) -> Result<Self, FoxbitError> {
+        let invalid = || FoxbitError::Validation(format!("{} is not a number: {}", name, value));
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, value.strip_prefix('+').unwrap_or(value)),
+        };
+        let (int, frac) = digits.split_once('.').unwrap_or((digits, ""));
+        if (int.is_empty() && frac.is_empty())
+            || !int.chars().chain(frac.chars()).all(|c| c.is_ascii_digit())
+        {
+            return Err(invalid());
+        }
+        let mantissa = format!("{}{}", int, frac)
+            .parse::<i128>()
+            .map_err(|_| invalid())?;
+        Ok(Fixed {
+            mantissa: if negative { -mantissa } else { mantissa },
+            scale: frac.len() as u32,
+        })
+    }
+
+    fn rescale(self, scale: u32) -> Option<i128> {
+        self.mantissa
+            .checked_mul(10i128.checked_pow(scale - self.scale)?)
+    }
+
+    fn compare(&self, other: &Fixed) -> Option<Ordering> {
+        let scale = self.scale.max(other.scale);
+        Some(self.rescale(scale)?.cmp(&other.rescale(scale)?))
+    }
+
+    fn to_number(self) -> Result<Number, FoxbitError> {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+        let digits = format!("{:0>width$}", digits, width = scale + 1);
+        let (int, frac) = digits.split_at(digits.len() - scale);
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let formatted = if frac.is_empty() {
+            format!("{}{}", sign, int)
+        } else {
+            format!("{}{}.{}", sign, int, frac)
+        };
+        formatted
+            .parse::<Number>()
+            .map_err(|_| FoxbitError::Validation(format!("{} is out of range", formatted)))
+    }
+}
+
+/// Increment to snap to: the market's, or one unit of the currency precision.
+fn step(increment: Option<&Number>, currency: &Currency) -> Result<Fixed, FoxbitError> {
+    let increment = match increment {
+        Some(increment) => increment,
+        None => {
+            return Ok(Fixed {
+                mantissa: 1,
+                scale: currency.precision as u32,
+            })
+        }
+    };
+    let step = Fixed::parse("increment", &increment.to_string())?;
+    if step.mantissa <= 0 {
+        return Err(FoxbitError::Validation(format!(
+            "market increment must be positive, got {}",
+            increment
+        )));
+    }
+    Ok(step)
+}
+
+fn snap(
+    name: &str,
+    value: &Number,
+    step: Fixed,
+    rounding: RoundingMode,
+) -> Result<Fixed, FoxbitError> {
+    let value = Fixed::parse(name, &value.to_string())?;
+    let out_of_range = || FoxbitError::Validation(format!("{} is out of range", name));
+
+    let scale = value.scale.max(step.scale);
+    let mantissa = value.rescale(scale).ok_or_else(out_of_range)?;
+    let increment = step.rescale(scale).ok_or_else(out_of_range)?;
+    let (steps, rest) = (mantissa / increment, mantissa % increment);
+
+    let steps = match rounding {
+        _ if rest == 0 => steps,
+        RoundingMode::Down => steps,
+        RoundingMode::Up => steps + mantissa.signum(),
+        RoundingMode::Nearest if rest.abs() * 2 >= increment => steps + mantissa.signum(),
+        RoundingMode::Nearest => steps,
+        RoundingMode::Strict => {
+            return Err(FoxbitError::Validation(format!(
+                "{} {} is not a multiple of {}",
+                name,
+                value.to_number()?,
+                step.to_number()?
+            )))
+        }
+    };
+
+    Ok(Fixed {
+        mantissa: steps.checked_mul(step.mantissa).ok_or_else(out_of_range)?,
+        scale: step.scale,
+    })
+}
+
+fn check_min(name: &str, value: &Fixed, min: Option<&Number>) -> Result<(), FoxbitError> {
+    if value.mantissa <= 0 {
+        return Err(FoxbitError::Validation(format!(
+            "{} must be positive once rounded, got {}",
+            name,
+            value.to_number()?
+        )));
+    }
+    if let Some(min) = min {
+        let min_value = Fixed::parse(name, &min.to_string())?;
+        if value.compare(&min_value) == Some(Ordering::Less) {
+            return Err(FoxbitError::Validation(format!(
+                "{} {} is below the market minimum of {}",
+                name,
+                value.to_number()?,
+                min
+            )));
+        }
+    }
+    Ok(())
+}
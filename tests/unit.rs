@@ -2,13 +2,42 @@
 mod tests {
     use std::collections::BTreeMap;
 
-    use reqwest::Client;
-    use rust_foxbit_api::Foxbit;
+    use reqwest::{Client, Method};
+    use rust_foxbit_api::fees::estimate_fee;
+    use rust_foxbit_api::helpers::{create_signature, get_prehash};
+    use rust_foxbit_api::foxbit::MAX_BATCH_ORDERS;
+    use rust_foxbit_api::document::{validate_document, DocumentKind};
+    use rust_foxbit_api::types::{
+        Amount, BankAccount, BankAccountType, CancelMode, CandleInterval, DepositFilter, DepositInfo,
+        LimitInterval, LimitOperation, Market, Number, Order, OrderFilter, OrderRef, OrderRequest, OrderState, OrderType,
+        OtcExecutionFilter, OtcQuote, OtcQuoteRequest, Side, TimeInForce, TradeFilter, TradeRole,
+        TradingFee, WithdrawInfo, WithdrawalFilter, WithdrawalRequest,
+    };
+    use rust_foxbit_api::pagination::PageBudget;
+    use rust_foxbit_api::registry::MarketRegistry;
+    use rust_foxbit_api::validation::{normalize_order, RoundingMode};
+    use rust_foxbit_api::{ApiErrorKind, BatchOrderFailure, Credentials, Foxbit, FoxbitError};
+    use futures::StreamExt;
     use serde_json::json;
-    use wiremock::matchers::{method, path, query_param};
-    use wiremock::{Mock, MockServer, ResponseTemplate};
+    use wiremock::matchers::{body_json, header, header_exists, method, path, query_param};
+    use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate, Times};
 
     use std::env;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    fn credentials() -> Credentials {
+        Credentials::new("access_key", "api_secret")
+    }
+
+    /// Builds a `Number` from a string, whether or not the `decimal` feature
+    /// is enabled.
+    fn num(value: &str) -> Number {
+        value.parse().unwrap()
+    }
+
+    fn to_f64(value: Amount) -> f64 {
+        value.to_string().parse().unwrap()
+    }
 
     #[tokio::test]
     async fn test_list_currencies() {
@@ -40,7 +69,7 @@ mod tests {
         };
 
         let client: Client = Client::new();
-        let foxbit = Foxbit::new(client, api_url);
+        let foxbit = Foxbit::public(client, api_url);
 
         let result = foxbit.list_currencies().await;
         assert!(result.is_ok());
@@ -104,7 +133,7 @@ mod tests {
         };
 
         let client: Client = Client::new();
-        let foxbit = Foxbit::new(client, api_url);
+        let foxbit = Foxbit::public(client, api_url);
 
         let result = foxbit.list_markets().await;
         assert!(result.is_ok());
@@ -114,10 +143,10 @@ mod tests {
 
         for market in markets {
             assert!(market.symbol.is_some(), "Market symbol should be present");
-            assert!(market.quantity_min.expect("REASON").parse::<f64>().is_ok(), "Quantity min should be a valid number");
-            assert!(market.quantity_increment.expect("REASON").parse::<f64>().is_ok(), "Quantity increment should be a valid number");
-            assert!(market.price_min.expect("REASON").parse::<f64>().is_ok(), "Price min should be a valid number");
-            assert!(market.price_increment.expect("REASON").parse::<f64>().is_ok(), "Price increment should be a valid number");
+            assert!(market.quantity_min.expect("REASON").to_string().parse::<f64>().is_ok(), "Quantity min should be a valid number");
+            assert!(market.quantity_increment.expect("REASON").to_string().parse::<f64>().is_ok(), "Quantity increment should be a valid number");
+            assert!(market.price_min.expect("REASON").to_string().parse::<f64>().is_ok(), "Price min should be a valid number");
+            assert!(market.price_increment.expect("REASON").to_string().parse::<f64>().is_ok(), "Price increment should be a valid number");
 
             let base = &market.base;
             assert!(base.symbol.is_some(), "Base asset symbol should be present");
@@ -163,10 +192,10 @@ mod tests {
         };
 
         let client: Client = Client::new();
-        let foxbit = Foxbit::new(client, api_url);
+        let foxbit = Foxbit::public(client, api_url);
 
         let result = foxbit
-            .get_market_quotation("buy", "usdt", "brl", Some("40"), None)
+            .get_market_quotation(Side::Buy, "usdt", "brl", Some("40"), None)
             .await;
 
         assert!(result.is_ok());
@@ -179,194 +208,192 @@ mod tests {
         assert!(quote.quote_amount.is_some(), "Quote amount should be present");
         assert!(quote.price.is_some(), "Price should be present");
 
-        assert!(quote.base_amount.as_ref().unwrap().parse::<f64>().is_ok(), "Base amount should be a valid number");
-        assert!(quote.quote_amount.as_ref().unwrap().parse::<f64>().is_ok(), "Quote amount should be a valid number");
-        assert!(quote.price.as_ref().unwrap().parse::<f64>().is_ok(), "Price should be a valid number");
+        assert!(quote.base_amount.as_ref().unwrap().to_string().parse::<f64>().is_ok(), "Base amount should be a valid number");
+        assert!(quote.quote_amount.as_ref().unwrap().to_string().parse::<f64>().is_ok(), "Quote amount should be a valid number");
+        assert!(quote.price.as_ref().unwrap().to_string().parse::<f64>().is_ok(), "Price should be a valid number");
     }
 
     #[tokio::test]
-    async fn test_get_order_book() {
+    async fn test_get_market_quotation_requires_quantity_or_amount() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("GET"))
-            .and(path("/markets/btcbrl/orderbook"))
-            .and(query_param("depth", "50"))
-            .respond_with(
-                ResponseTemplate::new(200)
-                        .set_body_json(json!({
-                            "sequence_id": 1234567890,
-                            "timestamp": 1234567890,
-                            "bids": [
-                                ["3.00000000", "300.00000000"],
-                                ["1.70000000", "310.00000000"]
-                            ],
-                            "asks": [
-                                ["3.00000000", "300.00000000"],
-                                ["2.00000000", "321.00000000"]
-                            ]
-                        }))
-                            .insert_header("content-type", "application/json"),
-                    )
-                    .mount(&mock_server)
-                    .await;
-
-        let api_url = match env::var("API_ENV") {
-            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
-            _ => mock_server.uri(),
-        };
-
+        let api_url = mock_server.uri();
         let client: Client = Client::new();
-        let foxbit = Foxbit::new(client, api_url);
+        let foxbit = Foxbit::public(client, api_url);
 
-        let mut query_params = BTreeMap::new();
-        query_params.insert("depth", "50");
+        let result = foxbit
+            .get_market_quotation(Side::Buy, "usdt", "brl", None, None)
+            .await;
 
-        let result = foxbit.get_order_book("btcbrl", 50).await;
-        assert!(result.is_ok());
+        assert!(
+            matches!(result, Err(FoxbitError::Validation(_))),
+            "Missing quantity and amount should be a validation error"
+        );
+    }
 
-        let orderbook = result.unwrap();
+    #[tokio::test]
+    async fn test_decode_error_keeps_body() {
+        let mock_server = MockServer::start().await;
 
-        assert!(orderbook.sequence_id > 0, "Sequence ID should be a positive integer");
-        assert!(orderbook.timestamp > 0, "Timestamp should be a positive integer");
-        assert!(!orderbook.bids.is_empty(), "Bids should not be empty");
-        assert!(!orderbook.asks.is_empty(), "Asks should not be empty");
+        Mock::given(method("GET"))
+            .and(path("/system/time"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("not json"))
+            .mount(&mock_server)
+            .await;
 
-        for bid in &orderbook.bids {
-            assert_eq!(bid.len(), 2, "Each bid should contain a price and quantity");
-            assert!(bid[0].parse::<f64>().is_ok(), "Bid price should be a valid number");
-            assert!(bid[1].parse::<f64>().is_ok(), "Bid quantity should be a valid number");
-        }
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::public(client, api_url);
 
-        for ask in &orderbook.asks {
-            assert_eq!(ask.len(), 2, "Each ask should contain a price and quantity");
-            assert!(ask[0].parse::<f64>().is_ok(), "Ask price should be a valid number");
-            assert!(ask[1].parse::<f64>().is_ok(), "Ask quantity should be a valid number");
+        match foxbit.get_current_time().await {
+            Err(FoxbitError::Decode { body, .. }) => assert_eq!(body, "not json"),
+            other => panic!("Expected a decode error, got {:?}", other),
         }
     }
 
-    #[tokio::test]
-    async fn test_get_candlesticks() {
-        let mock_server = MockServer::start().await;
+    async fn mount_empty_list(mock_server: &MockServer, list_path: &str) {
+        Mock::given(method("GET"))
+            .and(path(list_path))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "data": [] }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(mock_server)
+            .await;
+    }
 
+    async fn mount_btcbrl_market(mock_server: &MockServer) {
         Mock::given(method("GET"))
-            .and(path("/markets/btcbrl/candlesticks"))
-            .and(query_param("interval", "1d"))
-            .and(query_param("start_time", "2022-07-18T00:00"))
-            .and(query_param("end_time", "2022-08-19T12:00"))
+            .and(path("/markets"))
             .respond_with(
                 ResponseTemplate::new(200)
-                    .set_body_json(json!([
-                    [
-                        "1692918000000",
-                        "127772.05150000",
-                        "128467.99980000",
-                        "127750.01000000",
-                        "128353.99990000",
-                        "1692918060000",
-                        "0.17080431",
-                        "21866.35948786",
-                        66,
-                        "0.12073605",
-                        "15466.34096391"
-                    ],
-                    [
-                        "1692921600000",
-                        "128353.99990000",
-                        "128353.99990000",
-                        "127922.00030000",
-                        "128339.99990000",
-                        "1692921660000",
-                        "0.12355465",
-                        "15851.30631056",
-                        45,
-                        "0.11030870",
-                        "14156.75206627"
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "symbol": "btcbrl",
+                            "quantity_min": "0.00001",
+                            "quantity_increment": "0.00001",
+                            "price_min": "1.00",
+                            "price_increment": "0.01",
+                            "base": { "symbol": "btc", "name": "Bitcoin", "type": "CRYPTO", "precision": 8 },
+                            "quote": { "symbol": "brl", "name": "Real", "type": "FIAT", "precision": 2 }
+                        }
                     ]
-                ]))
+                }))
                     .insert_header("content-type", "application/json"),
             )
-            .mount(&mock_server)
+            .mount(mock_server)
             .await;
+        mount_empty_list(mock_server, "/currencies").await;
+    }
 
-        let api_url = match env::var("API_ENV") {
-            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
-            _ => mock_server.uri(),
-        };
+    #[tokio::test]
+    async fn test_api_error_payload() {
+        let mock_server = MockServer::start().await;
+        mount_btcbrl_market(&mock_server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/orders"))
+            .respond_with(
+                ResponseTemplate::new(422)
+                    .set_body_json(json!({
+                    "error": {
+                        "code": 2001,
+                        "message": "Insufficient balance",
+                        "details": ["quantity exceeds available balance"]
+                    }
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
 
+        let api_url = mock_server.uri();
         let client: Client = Client::new();
-        let foxbit = Foxbit::new(client, api_url);
+        let foxbit = Foxbit::new(client, api_url, credentials());
 
         let result = foxbit
-            .get_candlesticks("btcbrl", "1d", "2022-07-18T00:00", "2022-08-19T12:00")
+            .create_order(
+                Side::Buy,
+                OrderType::Market,
+                "btcbrl",
+                num("0.42"),
+                Some("123456789"),
+                Some("remark"),
+            )
             .await;
 
-        assert!(result.is_ok());
-
-        let candlesticks = result.unwrap();
-        assert!(!candlesticks.is_empty(), "Candlesticks list should not be empty");
+        let error = result.expect_err("Order should be rejected");
+        let api_error = error.api_error().expect("Error should carry the Foxbit payload");
+        assert_eq!(api_error.status, 422);
+        assert_eq!(api_error.code, Some(2001));
+        assert_eq!(api_error.kind, ApiErrorKind::InsufficientBalance);
+        assert_eq!(api_error.message, "Insufficient balance");
+        assert_eq!(api_error.details.len(), 1);
+        assert!(!error.is_transient(), "Rejected orders should not be retried");
+    }
 
-        for candlestick in candlesticks {
-            assert!(candlestick.open_time.parse::<i64>().is_ok(), "Open time should be a valid timestamp");
-            assert!(candlestick.close_time.parse::<i64>().is_ok(), "Close time should be a valid timestamp");
+    #[tokio::test]
+    async fn test_http_error_without_payload() {
+        let mock_server = MockServer::start().await;
 
-            assert!(candlestick.open_price.parse::<f64>().is_ok(), "Open price should be a valid number");
-            assert!(candlestick.high_price.parse::<f64>().is_ok(), "High price should be a valid number");
-            assert!(candlestick.low_price.parse::<f64>().is_ok(), "Low price should be a valid number");
-            assert!(candlestick.close_price.parse::<f64>().is_ok(), "Close price should be a valid number");
+        Mock::given(method("GET"))
+            .and(path("/system/time"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+            .mount(&mock_server)
+            .await;
 
-            assert!(candlestick.volume.parse::<f64>().is_ok(), "Volume should be a valid number");
-            assert!(candlestick.quote_asset_volume.parse::<f64>().is_ok(), "Quote asset volume should be a valid number");
-            assert!(candlestick.number_of_trades >= 0, "Number of trades should be a non-negative integer");
-            assert!(candlestick.taker_buy_base_asset_volume.parse::<f64>().is_ok(), "Taker buy base asset volume should be a valid number");
-            assert!(candlestick.taker_buy_quote_asset_volume.parse::<f64>().is_ok(), "Taker buy quote asset volume should be a valid number");
-        }
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::public(client, api_url);
+
+        let error = foxbit.get_current_time().await.expect_err("Request should fail");
+        assert!(
+            matches!(&error, FoxbitError::Http { status: 503, body } if body == "Service Unavailable"),
+            "Unexpected error: {:?}",
+            error
+        );
+        assert!(error.is_transient(), "Service unavailable should be retryable");
     }
 
     #[tokio::test]
-    async fn test_list_banks() {
+    async fn test_builder() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/banks"))
+            .and(path("/me"))
+            .and(header_exists("X-FB-ACCESS-SIGNATURE"))
+            .and(header("X-FB-ACCESS-KEY", "access_key"))
+            .and(header("user-agent", "foxbit-bot/1.0"))
             .respond_with(
                 ResponseTemplate::new(200)
                     .set_body_json(json!({
-                    "data": [
-                        {
-                            "abbreviation": "BB",
-                            "name": "Banco do Brasil",
-                            "code": 1
-                        }
-                    ]
+                    "sn": "FTEF4ISD4SV7QB",
+                    "email": "cs.eduardo@icloud.com",
+                    "level": 30,
+                    "created_at": "2018-07-10T17:45:18.000Z",
+                    "disabled": false
                 }))
                     .insert_header("content-type", "application/json"),
             )
             .mount(&mock_server)
             .await;
 
-        let api_url = match env::var("API_ENV") {
-            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
-            _ => mock_server.uri(),
-        };
-
-        let client: Client = Client::new();
-        let foxbit = Foxbit::new(client, api_url);
+        let foxbit = Foxbit::builder()
+            .api_url(mock_server.uri())
+            .credentials(credentials())
+            .timeout(Duration::from_secs(10))
+            .user_agent("foxbit-bot/1.0")
+            .build()
+            .expect("Builder should create a client");
 
-        let result = foxbit.list_banks().await;
+        let result = foxbit.get_current_member_details().await;
         assert!(result.is_ok());
-
-        let banks = result.unwrap();
-        assert!(!banks.is_empty(), "Banks list should not be empty");
-
-        for bank in banks {
-            assert!(!bank.abbreviation.is_empty(), "Abbreviation should not be empty");
-            assert!(!bank.name.is_empty(), "Bank name should not be empty");
-            assert!(bank.code > 0, "Bank code should be a positive integer");
-        }
     }
 
     #[tokio::test]
-    async fn test_get_current_time() {
+    async fn test_public_client_skips_signing() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
@@ -382,104 +409,1296 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let api_url = match env::var("API_ENV") {
-            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
-            _ => mock_server.uri(),
-        };
-
-        let client: Client = Client::new();
-        let foxbit = Foxbit::new(client, api_url);
+        let foxbit = Foxbit::builder()
+            .api_url(mock_server.uri())
+            .build()
+            .expect("Builder should create a public client without credentials");
 
         let result = foxbit.get_current_time().await;
         assert!(result.is_ok());
 
-        let current_time = result.unwrap();
+        let requests = mock_server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(
+            !requests[0]
+                .headers
+                .keys()
+                .any(|name| name.as_str().eq_ignore_ascii_case("X-FB-ACCESS-SIGNATURE")),
+            "Public requests should not be signed"
+        );
+        assert!(
+            !requests[0]
+                .headers
+                .keys()
+                .any(|name| name.as_str().eq_ignore_ascii_case("X-FB-ACCESS-KEY")),
+            "Public requests should not carry an access key"
+        );
+    }
 
-        assert!(!current_time.iso.is_empty(), "ISO timestamp should not be empty");
-        assert!(current_time.iso.ends_with('Z'), "ISO timestamp should end with 'Z'");
-        assert!(current_time.timestamp > 0, "Timestamp should be a positive integer");
+    #[test]
+    fn test_builder_rejects_timeout_with_custom_client() {
+        let result = Foxbit::builder()
+            .credentials(credentials())
+            .http_client(Client::new())
+            .timeout(Duration::from_secs(10))
+            .build();
+        assert!(matches!(result, Err(FoxbitError::Config(_))));
     }
 
     #[tokio::test]
-    async fn test_get_current_member_details() {
+    async fn test_invalid_access_key_is_config_error() {
         let mock_server = MockServer::start().await;
+        let foxbit = Foxbit::new(Client::new(), mock_server.uri(), Credentials::new("my-key\n", "my-secret"));
 
-        Mock::given(method("GET"))
-            .and(path("/me"))
+        let result = foxbit.get_current_member_details().await;
+        assert!(matches!(result, Err(FoxbitError::Config(_))));
+        let requests = mock_server.received_requests().await.unwrap();
+        assert!(requests.iter().all(|request| request.url.path() != "/me"), "Nothing should be signed");
+    }
+
+    #[test]
+    fn test_credentials_debug_redacts_secret() {
+        let debug = format!("{:?}", Credentials::new("my-key", "my-secret"));
+        assert!(debug.contains("my-key"), "Access key should be printed");
+        assert!(!debug.contains("my-secret"), "Secret should not be printed");
+    }
+
+    #[test]
+    fn test_get_prehash_uses_method() {
+        let prehash = get_prehash(
+            &Method::PUT,
+            "/rest/v3/orders/cancel",
+            "1637342699407",
+            "",
+            r#"{"type":"ALL"}"#,
+        );
+        assert_eq!(prehash, r#"1637342699407PUT/rest/v3/orders/cancel{"type":"ALL"}"#);
+
+        let prehash = get_prehash(
+            &Method::DELETE,
+            "/rest/v3/orders/1",
+            "1637342699407",
+            "market_symbol=btcbrl",
+            "",
+        );
+        assert_eq!(prehash, "1637342699407DELETE/rest/v3/orders/1market_symbol=btcbrl");
+    }
+
+    #[tokio::test]
+    async fn test_signed_body_matches_sent_body() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("PUT"))
+            .and(path("/rest/v3/orders/cancel"))
             .respond_with(
                 ResponseTemplate::new(200)
-                    .set_body_json(json!({
-                    "sn": "FTEF4ISD4SV7QB",
-                    "email": "cs.eduardo@icloud.com",
-                    "level": 30,
-                    "created_at": "2018-07-10T17:45:18.000Z",
-                    "disabled": false
-                }))
+                    .set_body_json(json!({ "data": [] }))
                     .insert_header("content-type", "application/json"),
             )
             .mount(&mock_server)
             .await;
 
-        let api_url = match env::var("API_ENV") {
-            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
-            _ => mock_server.uri(),
-        };
-
+        // The signature covers the base path of the API URL too.
+        let api_url = format!("{}/rest/v3", mock_server.uri());
         let client: Client = Client::new();
-        let foxbit = Foxbit::new(client, api_url);
+        let foxbit = Foxbit::new(client, api_url, credentials());
 
-        let result = foxbit.get_current_member_details().await;
+        let result = foxbit.cancel_orders(CancelMode::All).await;
         assert!(result.is_ok());
 
-        let current_member_details = result.unwrap();
+        let requests = mock_server.received_requests().await.unwrap();
+        let request = requests
+            .iter()
+            .find(|request| request.url.path() == "/rest/v3/orders/cancel")
+            .unwrap();
+        let header_value = |name: &str| {
+            request
+                .headers
+                .iter()
+                .find(|(key, _)| key.as_str().eq_ignore_ascii_case(name))
+                .map(|(_, values)| values.as_str().to_string())
+                .expect("Header should be present")
+        };
 
-        assert!(!current_member_details.sn.is_empty(), "SN should not be empty");
-        assert!(current_member_details.email.contains('@'), "Email should be a valid email format");
-        assert!(current_member_details.level > 0, "Level should be a positive integer");
-        assert!(!current_member_details.created_at.is_empty(), "Created_at should not be empty");
-        assert!(!current_member_details.disabled, "Disabled should be false");
+        let timestamp = header_value("X-FB-ACCESS-TIMESTAMP");
+        let body = String::from_utf8(request.body.clone()).unwrap();
+        let prehash = get_prehash(&Method::PUT, request.url.path(), &timestamp, "", &body);
+        let expected = create_signature(&prehash, "api_secret");
+
+        assert_eq!(header_value("X-FB-ACCESS-SIGNATURE"), expected);
     }
 
     #[tokio::test]
-    async fn test_create_order() {
+    async fn test_signed_query_matches_sent_query() {
         let mock_server = MockServer::start().await;
 
-        Mock::given(method("POST"))
-            .and(path("/orders"))
+        Mock::given(method("GET"))
+            .and(path("/trades"))
+            .and(query_param("start_time", "2024-08-28T00:00:00.000Z"))
             .respond_with(
                 ResponseTemplate::new(200)
-                    .set_body_json(json!({
-                      "id": 1234567890,
-                      "sn": "OKMAKSDHRVVREK",
-                      "client_order_id": "451637946501"
-                    }))
+                    .set_body_json(json!({ "data": [] }))
                     .insert_header("content-type", "application/json"),
             )
             .mount(&mock_server)
             .await;
 
         let api_url = mock_server.uri();
-        //let api_url = "https://api.foxbit.com.br/rest/v3".into(); // Uncomment to test Foxbit Production.
         let client: Client = Client::new();
-        let foxbit = Foxbit::new(client, api_url);
+        let foxbit = Foxbit::new(client, api_url, credentials());
 
         let result = foxbit
-            .create_order(
-                "BUY",
-                "MARKET",
+            .list_trades(
+                "2024-08-28T00:00:00.000Z",
+                "2024-08-29T20:00:22.013Z",
+                10,
+                1,
                 "btcbrl",
-                "0.42",
-                Some("123456789"),
-                Some("remark"),
             )
             .await;
-
         assert!(result.is_ok());
 
-        let create_order_response = result.unwrap();
-        assert_eq!(create_order_response.id, 1234567890);
-        assert_eq!(create_order_response.sn, "OKMAKSDHRVVREK");
-        assert_eq!(create_order_response.client_order_id, "451637946501")
+        let requests = mock_server.received_requests().await.unwrap();
+        let request = requests.iter().find(|request| request.url.path() == "/trades").unwrap();
+        let header_value = |name: &str| {
+            request
+                .headers
+                .iter()
+                .find(|(key, _)| key.as_str().eq_ignore_ascii_case(name))
+                .map(|(_, values)| values.as_str().to_string())
+                .expect("Header should be present")
+        };
+
+        let timestamp = header_value("X-FB-ACCESS-TIMESTAMP");
+        let query_string = request.url.query().unwrap_or("");
+        let prehash = get_prehash(&Method::GET, request.url.path(), &timestamp, query_string, "");
+        let expected = create_signature(&prehash, "api_secret");
+
+        assert_eq!(header_value("X-FB-ACCESS-SIGNATURE"), expected);
+    }
+
+    #[tokio::test]
+    async fn test_clock_sync_offsets_timestamps() {
+        let mock_server = MockServer::start().await;
+        let local_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        let server_now = local_now + 3_600_000;
+
+        Mock::given(method("GET"))
+            .and(path("/system/time"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "iso": "2021-06-15T18:00:00.123Z",
+                    "timestamp": server_now
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/me"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "sn": "FTEF4ISD4SV7QB",
+                    "email": "cs.eduardo@icloud.com",
+                    "level": 30,
+                    "created_at": "2018-07-10T17:45:18.000Z",
+                    "disabled": false
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let foxbit = Foxbit::builder()
+            .api_url(mock_server.uri())
+            .credentials(credentials())
+            .clock_sync(Duration::from_secs(60))
+            .build()
+            .unwrap();
+
+        assert!(foxbit.clock().round_trip_time().is_none(), "Clock should not be synced yet");
+
+        let result = foxbit.get_current_member_details().await;
+        assert!(result.is_ok());
+
+        let offset = foxbit.clock().offset_millis();
+        assert!((offset - 3_600_000).abs() < 5_000, "Offset should be about one hour, got {}", offset);
+        assert!(foxbit.clock().round_trip_time().is_some(), "Round-trip time should be measured");
+
+        let requests = mock_server.received_requests().await.unwrap();
+        assert_eq!(requests[0].url.path(), "/system/time", "Clock should be synced first");
+        let timestamp: u64 = requests[1]
+            .headers
+            .iter()
+            .find(|(key, _)| key.as_str().eq_ignore_ascii_case("X-FB-ACCESS-TIMESTAMP"))
+            .map(|(_, values)| values.as_str().parse().unwrap())
+            .expect("Timestamp header should be present");
+        assert!(timestamp.abs_diff(server_now) < 5_000, "Timestamp should follow the server clock");
+    }
+
+    #[tokio::test]
+    async fn test_default_clock_syncs_once_for_concurrent_requests() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/system/time"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "iso": "2021-06-15T18:00:00.123Z",
+                    "timestamp": SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64
+                }))
+                    .set_delay(Duration::from_millis(100))
+                    .insert_header("content-type", "application/json"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/me"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "sn": "FTEF4ISD4SV7QB",
+                    "email": "cs.eduardo@icloud.com",
+                    "level": 30,
+                    "created_at": "2018-07-10T17:45:18.000Z",
+                    "disabled": false
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let foxbit = Foxbit::new(Client::new(), mock_server.uri(), credentials());
+        let (first, second, third) = tokio::join!(
+            foxbit.get_current_member_details(),
+            foxbit.get_current_member_details(),
+            foxbit.get_current_member_details()
+        );
+        assert!(first.is_ok() && second.is_ok() && third.is_ok());
+        assert!(foxbit.clock().last_synced().is_some(), "Clock should be synced on start-up");
+
+        let requests = mock_server.received_requests().await.unwrap();
+        assert_eq!(requests[0].url.path(), "/system/time", "Clock should be synced first");
+    }
+
+    #[tokio::test]
+    async fn test_failed_clock_sync_is_not_retried_on_every_request() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/system/time"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/me"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "sn": "FTEF4ISD4SV7QB",
+                    "email": "cs.eduardo@icloud.com",
+                    "level": 30,
+                    "created_at": "2018-07-10T17:45:18.000Z",
+                    "disabled": false
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let foxbit = Foxbit::new(Client::new(), mock_server.uri(), credentials());
+        assert!(foxbit.get_current_member_details().await.is_ok());
+        assert!(foxbit.get_current_member_details().await.is_ok());
+        assert!(foxbit.clock().last_synced().is_none(), "Clock should not be synced");
+
+        let requests = mock_server.received_requests().await.unwrap();
+        let paths: Vec<&str> = requests.iter().map(|r| r.url.path()).collect();
+        assert_eq!(paths, vec!["/system/time", "/me", "/me"]);
+    }
+
+    #[tokio::test]
+    async fn test_resync_on_rejected_timestamp() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/system/time"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "iso": "2021-06-15T18:00:00.123Z",
+                    "timestamp": 1637342699407_u64
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/me"))
+            .respond_with(
+                ResponseTemplate::new(401)
+                    .set_body_json(json!({
+                    "error": {
+                        "code": 1003,
+                        "message": "Invalid timestamp"
+                    }
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .up_to_n_times(1)
+            .with_priority(1)
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/me"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "sn": "FTEF4ISD4SV7QB",
+                    "email": "cs.eduardo@icloud.com",
+                    "level": 30,
+                    "created_at": "2018-07-10T17:45:18.000Z",
+                    "disabled": false
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let foxbit = Foxbit::builder()
+            .api_url(mock_server.uri())
+            .credentials(credentials())
+            .skip_clock_sync()
+            .build()
+            .unwrap();
+
+        let result = foxbit.get_current_member_details().await;
+        assert!(result.is_ok(), "Request should succeed after re-syncing: {:?}", result);
+
+        let requests = mock_server.received_requests().await.unwrap();
+        let paths: Vec<&str> = requests.iter().map(|r| r.url.path()).collect();
+        assert_eq!(paths, vec!["/me", "/system/time", "/me"]);
+        assert!(foxbit.clock().offset_millis() < 0, "Offset should follow the server clock");
+    }
+
+    #[tokio::test]
+    async fn test_get_order_book() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/markets/btcbrl/orderbook"))
+            .and(query_param("depth", "50"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                        .set_body_json(json!({
+                            "sequence_id": 1234567890,
+                            "timestamp": 1234567890,
+                            "bids": [
+                                ["3.00000000", "300.00000000"],
+                                ["1.70000000", "310.00000000"]
+                            ],
+                            "asks": [
+                                ["3.00000000", "300.00000000"],
+                                ["2.00000000", "321.00000000"]
+                            ]
+                        }))
+                            .insert_header("content-type", "application/json"),
+                    )
+                    .mount(&mock_server)
+                    .await;
+
+        let api_url = match env::var("API_ENV") {
+            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
+            _ => mock_server.uri(),
+        };
+
+        let client: Client = Client::new();
+        let foxbit = Foxbit::public(client, api_url);
+
+        let mut query_params = BTreeMap::new();
+        query_params.insert("depth", "50");
+
+        let result = foxbit.get_order_book("btcbrl", 50).await;
+        assert!(result.is_ok());
+
+        let orderbook = result.unwrap();
+
+        assert!(orderbook.sequence_id > 0, "Sequence ID should be a positive integer");
+        assert!(orderbook.timestamp > 0, "Timestamp should be a positive integer");
+        assert!(!orderbook.bids.is_empty(), "Bids should not be empty");
+        assert!(!orderbook.asks.is_empty(), "Asks should not be empty");
+
+        for bid in &orderbook.bids {
+            assert_eq!(bid.len(), 2, "Each bid should contain a price and quantity");
+            assert!(bid[0].to_string().parse::<f64>().is_ok(), "Bid price should be a valid number");
+            assert!(bid[1].to_string().parse::<f64>().is_ok(), "Bid quantity should be a valid number");
+        }
+
+        for ask in &orderbook.asks {
+            assert_eq!(ask.len(), 2, "Each ask should contain a price and quantity");
+            assert!(ask[0].to_string().parse::<f64>().is_ok(), "Ask price should be a valid number");
+            assert!(ask[1].to_string().parse::<f64>().is_ok(), "Ask quantity should be a valid number");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_candlesticks() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/markets/btcbrl/candlesticks"))
+            .and(query_param("interval", "1d"))
+            .and(query_param("start_time", "2022-07-18T00:00"))
+            .and(query_param("end_time", "2022-08-19T12:00"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!([
+                    [
+                        "1692918000000",
+                        "127772.05150000",
+                        "128467.99980000",
+                        "127750.01000000",
+                        "128353.99990000",
+                        "1692918060000",
+                        "0.17080431",
+                        "21866.35948786",
+                        66,
+                        "0.12073605",
+                        "15466.34096391"
+                    ],
+                    [
+                        "1692921600000",
+                        "128353.99990000",
+                        "128353.99990000",
+                        "127922.00030000",
+                        "128339.99990000",
+                        "1692921660000",
+                        "0.12355465",
+                        "15851.30631056",
+                        45,
+                        "0.11030870",
+                        "14156.75206627"
+                    ]
+                ]))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = match env::var("API_ENV") {
+            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
+            _ => mock_server.uri(),
+        };
+
+        let client: Client = Client::new();
+        let foxbit = Foxbit::public(client, api_url);
+
+        let result = foxbit
+            .get_candlesticks("btcbrl", CandleInterval::OneDay, "2022-07-18T00:00", "2022-08-19T12:00")
+            .await;
+
+        assert!(result.is_ok());
+
+        let candlesticks = result.unwrap();
+        assert!(!candlesticks.is_empty(), "Candlesticks list should not be empty");
+
+        for candlestick in candlesticks {
+            assert!(candlestick.open_time.parse::<i64>().is_ok(), "Open time should be a valid timestamp");
+            assert!(candlestick.close_time.parse::<i64>().is_ok(), "Close time should be a valid timestamp");
+
+            assert!(candlestick.open_price.to_string().parse::<f64>().is_ok(), "Open price should be a valid number");
+            assert!(candlestick.high_price.to_string().parse::<f64>().is_ok(), "High price should be a valid number");
+            assert!(candlestick.low_price.to_string().parse::<f64>().is_ok(), "Low price should be a valid number");
+            assert!(candlestick.close_price.to_string().parse::<f64>().is_ok(), "Close price should be a valid number");
+
+            assert!(candlestick.volume.to_string().parse::<f64>().is_ok(), "Volume should be a valid number");
+            assert!(candlestick.quote_asset_volume.to_string().parse::<f64>().is_ok(), "Quote asset volume should be a valid number");
+            assert!(candlestick.number_of_trades >= 0, "Number of trades should be a non-negative integer");
+            assert!(candlestick.taker_buy_base_asset_volume.to_string().parse::<f64>().is_ok(), "Taker buy base asset volume should be a valid number");
+            assert!(candlestick.taker_buy_quote_asset_volume.to_string().parse::<f64>().is_ok(), "Taker buy quote asset volume should be a valid number");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_ticker() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/markets/btcbrl/ticker/24hr"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "market_symbol": "btcbrl",
+                            "last_trade": {
+                                "price": "358504.69",
+                                "volume": "0.00002",
+                                "date": "2024-01-01T00:00:00.000Z"
+                            },
+                            "rolling_24h": {
+                                "price_change": "3050.53",
+                                "price_change_percent": "0.86",
+                                "volume": "1.32",
+                                "trades_count": 1043,
+                                "open": "355454.16",
+                                "high": "358504.69",
+                                "low": "354598.48"
+                            },
+                            "best": {
+                                "ask": {
+                                    "price": "358504.69",
+                                    "volume": "0.00214"
+                                },
+                                "bid": {
+                                    "price": "358000.01",
+                                    "volume": "0.00153"
+                                }
+                            }
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = match env::var("API_ENV") {
+            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
+            _ => mock_server.uri(),
+        };
+
+        let client: Client = Client::new();
+        let foxbit = Foxbit::public(client, api_url);
+
+        let result = foxbit.get_ticker("btcbrl").await;
+        assert!(result.is_ok());
+
+        let ticker = result.unwrap();
+        assert_eq!(ticker.market_symbol, "btcbrl", "Market symbol should match the requested market");
+        assert!(ticker.last_trade.price.to_string().parse::<f64>().is_ok(), "Last price should be a valid number");
+        assert!(ticker.rolling_24h.high.to_string().parse::<f64>().is_ok(), "24h high should be a valid number");
+        assert!(ticker.rolling_24h.low.to_string().parse::<f64>().is_ok(), "24h low should be a valid number");
+        assert!(ticker.rolling_24h.volume.to_string().parse::<f64>().is_ok(), "24h volume should be a valid number");
+        assert!(ticker.rolling_24h.price_change.to_string().parse::<f64>().is_ok(), "24h change should be a valid number");
+
+        let ask = ticker.best.ask.expect("Best ask should be present");
+        let bid = ticker.best.bid.expect("Best bid should be present");
+        assert!(ask.price.to_string().parse::<f64>().unwrap() >= bid.price.to_string().parse::<f64>().unwrap(), "Best ask should not be below best bid");
+    }
+
+    #[tokio::test]
+    async fn test_list_tickers() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/markets/ticker/24hr"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "market_symbol": "btcbrl",
+                            "last_trade": {
+                                "price": "358504.69",
+                                "volume": "0.00002",
+                                "date": "2024-01-01T00:00:00.000Z"
+                            },
+                            "rolling_24h": {
+                                "price_change": "3050.53",
+                                "price_change_percent": "0.86",
+                                "volume": "1.32",
+                                "trades_count": 1043,
+                                "open": "355454.16",
+                                "high": "358504.69",
+                                "low": "354598.48"
+                            },
+                            "best": {
+                                "ask": {
+                                    "price": "358504.69",
+                                    "volume": "0.00214"
+                                },
+                                "bid": {
+                                    "price": "358000.01",
+                                    "volume": "0.00153"
+                                }
+                            }
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = match env::var("API_ENV") {
+            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
+            _ => mock_server.uri(),
+        };
+
+        let client: Client = Client::new();
+        let foxbit = Foxbit::public(client, api_url);
+
+        let result = foxbit.list_tickers().await;
+        assert!(result.is_ok());
+
+        let tickers = result.unwrap();
+        assert!(!tickers.is_empty(), "Tickers list should not be empty");
+
+        for ticker in tickers {
+            assert!(!ticker.market_symbol.is_empty(), "Market symbol should not be empty");
+            assert!(ticker.last_trade.price.to_string().parse::<f64>().is_ok(), "Last price should be a valid number");
+            assert!(ticker.rolling_24h.price_change_percent.to_string().parse::<f64>().is_ok(), "24h change percent should be a valid number");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_market_trades() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/markets/btcbrl/trades/history"))
+            .and(query_param("start_time", "2024-08-28T00:00:00.000Z"))
+            .and(query_param("page_size", "2"))
+            .and(query_param("page", "1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "id": 1234567890,
+                            "price": "290000.0",
+                            "volume": "0.01",
+                            "taker_side": "BUY",
+                            "created_at": "2024-08-28T10:00:00.000Z"
+                        },
+                        {
+                            "id": 1234567891,
+                            "price": "290100.0",
+                            "volume": "0.02",
+                            "taker_side": "SELL",
+                            "created_at": "2024-08-28T10:00:01.000Z"
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = match env::var("API_ENV") {
+            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
+            _ => mock_server.uri(),
+        };
+
+        let client: Client = Client::new();
+        let foxbit = Foxbit::public(client, api_url);
+
+        let result = foxbit
+            .get_market_trades("btcbrl", Some("2024-08-28T00:00:00.000Z"), None, Some(2), Some(1))
+            .await;
+        assert!(result.is_ok());
+
+        let trades = result.unwrap();
+        assert!(!trades.is_empty(), "Trades list should not be empty");
+
+        for trade in trades {
+            assert!(trade.id > 0, "Trade ID should be a positive integer");
+            assert!(trade.price.to_string().parse::<f64>().is_ok(), "Price should be a valid number");
+            assert!(trade.volume.to_string().parse::<f64>().is_ok(), "Volume should be a valid number");
+            assert!([Side::Buy, Side::Sell].contains(&trade.taker_side), "Taker side should be either BUY or SELL");
+            assert!(trade.created_at.ends_with('Z'), "Created_at should end with 'Z' for UTC format");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_banks() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/banks"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "abbreviation": "BB",
+                            "name": "Banco do Brasil",
+                            "code": 1
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = match env::var("API_ENV") {
+            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
+            _ => mock_server.uri(),
+        };
+
+        let client: Client = Client::new();
+        let foxbit = Foxbit::public(client, api_url);
+
+        let result = foxbit.list_banks().await;
+        assert!(result.is_ok());
+
+        let banks = result.unwrap();
+        assert!(!banks.is_empty(), "Banks list should not be empty");
+
+        for bank in banks {
+            assert!(!bank.abbreviation.is_empty(), "Abbreviation should not be empty");
+            assert!(!bank.name.is_empty(), "Bank name should not be empty");
+            assert!(bank.code > 0, "Bank code should be a positive integer");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_current_time() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/system/time"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "iso": "2021-06-15T18:00:00.123Z",
+                    "timestamp": 1637342699407_u64
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = match env::var("API_ENV") {
+            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
+            _ => mock_server.uri(),
+        };
+
+        let client: Client = Client::new();
+        let foxbit = Foxbit::public(client, api_url);
+
+        let result = foxbit.get_current_time().await;
+        assert!(result.is_ok());
+
+        let current_time = result.unwrap();
+
+        assert!(!current_time.iso.is_empty(), "ISO timestamp should not be empty");
+        assert!(current_time.iso.ends_with('Z'), "ISO timestamp should end with 'Z'");
+        assert!(current_time.timestamp > 0, "Timestamp should be a positive integer");
+    }
+
+    #[tokio::test]
+    async fn test_get_current_member_details() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/me"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "sn": "FTEF4ISD4SV7QB",
+                    "email": "cs.eduardo@icloud.com",
+                    "level": 30,
+                    "created_at": "2018-07-10T17:45:18.000Z",
+                    "disabled": false
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = match env::var("API_ENV") {
+            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
+            _ => mock_server.uri(),
+        };
+
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit.get_current_member_details().await;
+        assert!(result.is_ok());
+
+        let current_member_details = result.unwrap();
+
+        assert!(!current_member_details.sn.is_empty(), "SN should not be empty");
+        assert!(current_member_details.email.contains('@'), "Email should be a valid email format");
+        assert!(current_member_details.level > 0, "Level should be a positive integer");
+        assert!(!current_member_details.created_at.is_empty(), "Created_at should not be empty");
+        assert!(!current_member_details.disabled, "Disabled should be false");
+    }
+
+    #[tokio::test]
+    async fn test_get_trading_fees() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/me/fees/trading"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "market_symbol": "btcbrl",
+                            "maker": "0.0025",
+                            "taker": "0.005"
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit.get_trading_fees().await;
+        assert!(result.is_ok());
+
+        let trading_fees = result.unwrap();
+        assert!(!trading_fees.is_empty(), "Trading fees list should not be empty");
+
+        for trading_fee in trading_fees {
+            assert!(!trading_fee.market_symbol.is_empty(), "Market symbol should not be empty");
+            assert!(trading_fee.maker.to_string().parse::<f64>().is_ok(), "Maker fee should be a valid number");
+            assert!(trading_fee.taker.to_string().parse::<f64>().is_ok(), "Taker fee should be a valid number");
+        }
+    }
+
+    #[test]
+    fn test_estimate_fee() {
+        let market: Market = serde_json::from_value(json!({
+            "symbol": "btcbrl",
+            "quantity_min": "0.00001",
+            "quantity_increment": "0.00001",
+            "price_min": "1.0",
+            "price_increment": "0.01",
+            "base": { "symbol": "btc", "precision": 8 },
+            "quote": { "symbol": "brl", "precision": 2 }
+        }))
+        .unwrap();
+        let trading_fee: TradingFee = serde_json::from_value(json!({
+            "market_symbol": "btcbrl",
+            "maker": "0.0025",
+            "taker": "0.005"
+        }))
+        .unwrap();
+
+        let buy = estimate_fee(&market, &trading_fee, Side::Buy, TradeRole::Taker, &num("2"), &num("300000")).unwrap();
+        assert_eq!(buy.fee_currency, "btc", "Buy fees should be charged in the base currency");
+        assert!((to_f64(buy.fee) - 0.01).abs() < 1e-12);
+        assert!((to_f64(buy.net_amount) - 1.99).abs() < 1e-12);
+
+        let sell = estimate_fee(&market, &trading_fee, Side::Sell, TradeRole::Maker, &num("2"), &num("300000")).unwrap();
+        assert_eq!(sell.fee_currency, "brl", "Sell fees should be charged in the quote currency");
+        assert!((to_f64(sell.fee) - 1500.0).abs() < 1e-9);
+        assert!((to_f64(sell.net_amount) - 598500.0).abs() < 1e-9);
+
+        let invalid = estimate_fee(&market, &trading_fee, Side::from("HOLD"), TradeRole::Maker, &num("2"), &num("300000"));
+        assert!(matches!(invalid, Err(FoxbitError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_order() {
+        let mock_server = MockServer::start().await;
+        mount_btcbrl_market(&mock_server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/orders"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                      "id": 1234567890,
+                      "sn": "OKMAKSDHRVVREK",
+                      "client_order_id": "451637946501"
+                    }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        //let api_url = "https://api.foxbit.com.br/rest/v3".into(); // Uncomment to test Foxbit Production.
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit
+            .create_order(
+                Side::Buy,
+                OrderType::Market,
+                "btcbrl",
+                num("0.42"),
+                Some("123456789"),
+                Some("remark"),
+            )
+            .await;
+
+        assert!(result.is_ok());
+
+        let create_order_response = result.unwrap();
+        assert_eq!(create_order_response.id, 1234567890);
+        assert_eq!(create_order_response.sn, "OKMAKSDHRVVREK");
+        assert_eq!(create_order_response.client_order_id, "451637946501")
+    }
+
+    #[tokio::test]
+    async fn test_create_order_without_optional_fields() {
+        let mock_server = MockServer::start().await;
+        mount_btcbrl_market(&mock_server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/orders"))
+            .and(body_json(json!({
+                "side": "SELL",
+                "type": "MARKET",
+                "market_symbol": "btcbrl",
+                "quantity": "0.42000"
+            })))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .set_body_json(json!({
+                      "id": 1234567890,
+                      "sn": "OKMAKSDHRVVREK",
+                      "client_order_id": ""
+                    }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit
+            .create_order(Side::Sell, OrderType::Market, "btcbrl", num("0.42"), None, None)
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().id, 1234567890);
+    }
+
+    #[test]
+    fn test_order_request_builder() {
+        let limit = OrderRequest::limit(Side::Buy, "btcbrl")
+            .quantity(num("0.5"))
+            .price(num("300000"))
+            .time_in_force(TimeInForce::GoodTillCancelled)
+            .post_only()
+            .client_order_id("limit-1")
+            .build()
+            .unwrap();
+        assert_eq!(limit.r#type, OrderType::Limit);
+        assert_eq!(limit.post_only, Some(true));
+
+        let instant = OrderRequest::instant(Side::Buy, "btcbrl")
+            .amount(num("1000"))
+            .build()
+            .unwrap();
+        assert_eq!(instant.quantity, None);
+        assert_eq!(instant.amount, Some(num("1000")));
+
+        let stop_limit = OrderRequest::stop_limit(Side::Sell, "btcbrl")
+            .quantity(num("0.5"))
+            .price(num("290000"))
+            .stop_price(num("295000"))
+            .build();
+        assert!(stop_limit.is_ok());
+
+        let invalid = [
+            // Limit orders need a price
+            OrderRequest::limit(Side::Buy, "btcbrl").quantity(num("0.5")),
+            // Market orders have no price
+            OrderRequest::market(Side::Buy, "btcbrl")
+                .quantity(num("0.5"))
+                .price(num("300000")),
+            // Instant orders are sized by amount
+            OrderRequest::instant(Side::Buy, "btcbrl").quantity(num("0.5")),
+            // Stop-limit orders need a stop price
+            OrderRequest::stop_limit(Side::Sell, "btcbrl")
+                .quantity(num("0.5"))
+                .price(num("290000")),
+            // Post-only orders cannot be immediate
+            OrderRequest::limit(Side::Buy, "btcbrl")
+                .quantity(num("0.5"))
+                .price(num("300000"))
+                .time_in_force(TimeInForce::ImmediateOrCancel)
+                .post_only(),
+            OrderRequest::stop_limit(Side::Sell, "btcbrl")
+                .quantity(num("0.5"))
+                .price(num("290000"))
+                .stop_price(num("295000"))
+                .post_only(),
+        ];
+        for builder in invalid {
+            assert!(matches!(builder.build(), Err(FoxbitError::Validation(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_order() {
+        let mock_server = MockServer::start().await;
+        mount_btcbrl_market(&mock_server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/orders"))
+            .and(body_json(json!({
+                "side": "BUY",
+                "type": "LIMIT",
+                "market_symbol": "btcbrl",
+                "quantity": "0.50000",
+                "price": "300000.00",
+                "time_in_force": "IOC",
+                "client_order_id": "limit-1"
+            })))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .set_body_json(json!({
+                      "id": 1234567890,
+                      "sn": "OKMAKSDHRVVREK",
+                      "client_order_id": "limit-1"
+                    }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let order = OrderRequest::limit(Side::Buy, "btcbrl")
+            .quantity(num("0.5"))
+            .price(num("300000"))
+            .time_in_force(TimeInForce::ImmediateOrCancel)
+            .client_order_id("limit-1")
+            .build()
+            .unwrap();
+        let result = foxbit.submit_order(&order).await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().client_order_id, "limit-1");
+    }
+
+    #[tokio::test]
+    async fn test_create_orders_batch() {
+        let mock_server = MockServer::start().await;
+        mount_btcbrl_market(&mock_server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/orders/batch"))
+            .respond_with(
+                ResponseTemplate::new(207)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "id": 1234567890,
+                            "sn": "OKMAKSDHRVVREK",
+                            "client_order_id": "first"
+                        },
+                        {
+                            "client_order_id": "second",
+                            "error": {
+                                "code": 2001,
+                                "message": "Insufficient balance"
+                            }
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let mut first = OrderRequest::new(Side::Buy, OrderType::Market, "btcbrl", num("0.42"));
+        first.client_order_id = Some("first".into());
+        let mut second = OrderRequest::new(Side::Buy, OrderType::Market, "btcbrl", num("100"));
+        second.client_order_id = Some("second".into());
+
+        let result = foxbit.create_orders_batch(vec![first, second]).await;
+        assert!(result.is_ok());
+
+        let results = result.unwrap();
+        assert_eq!(results.len(), 2, "There should be one result per order");
+
+        assert_eq!(results[0].client_order_id.as_deref(), Some("first"));
+        let created = results[0].result.as_ref().expect("First order should be created");
+        assert_eq!(created.id, 1234567890);
+        assert_eq!(created.sn, "OKMAKSDHRVVREK");
+
+        assert_eq!(results[1].client_order_id.as_deref(), Some("second"));
+        let rejected = results[1].result.as_ref().expect_err("Second order should be rejected");
+        assert!(matches!(rejected, BatchOrderFailure::Rejected(_)));
+        assert_eq!(rejected.api_error().unwrap().kind, ApiErrorKind::InsufficientBalance);
+        assert_eq!(rejected.api_error().unwrap().status, 207, "Status should be the batch response's");
+    }
+
+    struct BatchResponder;
+
+    impl Respond for BatchResponder {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            let data: Vec<serde_json::Value> = body["requests"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .enumerate()
+                .map(|(i, order)| {
+                    json!({
+                        "id": i + 1,
+                        "sn": format!("SN{}", i),
+                        "client_order_id": order["client_order_id"]
+                    })
+                })
+                .collect();
+            ResponseTemplate::new(200).set_body_json(json!({ "data": data }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_orders_batch_chunks() {
+        let mock_server = MockServer::start().await;
+        mount_btcbrl_market(&mock_server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/orders/batch"))
+            .respond_with(BatchResponder)
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let orders: Vec<OrderRequest> = (0..MAX_BATCH_ORDERS + 5)
+            .map(|i| {
+                let mut order = OrderRequest::new(Side::Buy, OrderType::Market, "btcbrl", num("0.001"));
+                order.client_order_id = Some(format!("ladder-{}", i));
+                order
+            })
+            .collect();
+
+        let results = foxbit.create_orders_batch(orders).await.unwrap();
+        assert_eq!(results.len(), MAX_BATCH_ORDERS + 5, "Every order should have a result");
+
+        for (i, result) in results.iter().enumerate() {
+            let expected = format!("ladder-{}", i);
+            assert_eq!(result.client_order_id.as_deref(), Some(expected.as_str()));
+            assert_eq!(result.result.as_ref().unwrap().client_order_id, expected);
+        }
+
+        let requests = mock_server.received_requests().await.unwrap();
+        let batches = requests.iter().filter(|request| request.url.path() == "/orders/batch");
+        assert_eq!(batches.count(), 2, "Orders should be split into two batches");
+    }
+
+    #[tokio::test]
+    async fn test_create_orders_batch_keeps_results_of_earlier_chunks() {
+        let mock_server = MockServer::start().await;
+        mount_btcbrl_market(&mock_server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/orders/batch"))
+            .respond_with(BatchResponder)
+            .up_to_n_times(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/orders/batch"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let foxbit = Foxbit::new(Client::new(), mock_server.uri(), credentials());
+
+        let orders: Vec<OrderRequest> = (0..MAX_BATCH_ORDERS * 2 + 5)
+            .map(|i| {
+                let mut order = OrderRequest::new(Side::Buy, OrderType::Market, "btcbrl", num("0.001"));
+                order.client_order_id = Some(format!("ladder-{}", i));
+                order
+            })
+            .collect();
+
+        let results = foxbit.create_orders_batch(orders).await.unwrap();
+        assert_eq!(results.len(), MAX_BATCH_ORDERS * 2 + 5, "Every order should have a result");
+
+        for (i, result) in results.iter().enumerate() {
+            let expected = format!("ladder-{}", i);
+            assert_eq!(result.client_order_id.as_deref(), Some(expected.as_str()));
+            if i < MAX_BATCH_ORDERS {
+                assert_eq!(result.result.as_ref().unwrap().client_order_id, expected);
+            } else {
+                // The failed second batch and the unsent third one.
+                match &result.result {
+                    Err(BatchOrderFailure::BatchFailed(e)) => {
+                        assert!(matches!(e.as_ref(), FoxbitError::Http { status: 503, .. }))
+                    }
+                    other => panic!("Expected a failed batch, got {:?}", other),
+                }
+            }
+        }
+
+        let requests = mock_server.received_requests().await.unwrap();
+        let batches = requests.iter().filter(|request| request.url.path() == "/orders/batch");
+        assert_eq!(batches.count(), 2, "No batch should be sent after a failed one");
+    }
+
+    #[tokio::test]
+    async fn test_create_orders_batch_reports_invalid_orders() {
+        let mock_server = MockServer::start().await;
+        mount_btcbrl_market(&mock_server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/orders/batch"))
+            .respond_with(BatchResponder)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let foxbit = Foxbit::new(Client::new(), mock_server.uri(), credentials());
+
+        // The middle rung is below quantity_min.
+        let orders: Vec<OrderRequest> = ["0.001", "0.000001", "0.002"]
+            .iter()
+            .enumerate()
+            .map(|(i, quantity)| {
+                let mut order = OrderRequest::new(Side::Buy, OrderType::Market, "btcbrl", num(quantity));
+                order.client_order_id = Some(format!("ladder-{}", i));
+                order
+            })
+            .collect();
+
+        let results = foxbit.create_orders_batch(orders).await.unwrap();
+        assert_eq!(results.len(), 3, "Every order should have a result");
+
+        assert_eq!(results[0].result.as_ref().unwrap().client_order_id, "ladder-0");
+        assert_eq!(results[1].client_order_id.as_deref(), Some("ladder-1"));
+        assert!(matches!(results[1].result, Err(BatchOrderFailure::Invalid(_))));
+        assert_eq!(results[2].result.as_ref().unwrap().client_order_id, "ladder-2");
+
+        let requests = mock_server.received_requests().await.unwrap();
+        let batch = requests.iter().find(|request| request.url.path() == "/orders/batch").unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&batch.body).unwrap();
+        assert_eq!(body["requests"].as_array().unwrap().len(), 2, "Only valid orders should be sent");
     }
 
     #[tokio::test]
@@ -487,29 +1706,1457 @@ mod tests {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/orders"))
+            .and(path("/orders"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "id": "1234567890",
+                            "sn": "OKMAKSDHRVVREK",
+                            "client_order_id": "451637946501",
+                            "market_symbol": "btcbrl",
+                            "side": "BUY",
+                            "type": "LIMIT",
+                            "state": "ACTIVE",
+                            "price": "290000.0",
+                            "price_avg": "295333.3333",
+                            "quantity": "0.42",
+                            "quantity_executed": "0.41",
+                            "instant_amount": "290.0",
+                            "instant_amount_executed": "290.0",
+                            "created_at": "2021-02-15T22:06:32.999Z",
+                            "trades_count": 2,
+                            "remark": "A remarkable note for the order.",
+                            "funds_received": "290.0"
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = match env::var("API_ENV") {
+            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
+            _ => mock_server.uri(),
+        };
+
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit
+            .list_orders(
+                "2024-08-28T00:00:00.000Z",
+                "2024-08-29T20:00:22.013Z",
+                10,
+                1,
+                "btcbrl",
+                OrderState::Filled,
+                Side::Buy,
+            )
+            .await;
+
+        assert!(result.is_ok());
+
+        let orders = result.unwrap();
+        assert!(!orders.is_empty(), "Orders list should not be empty");
+
+        for order in orders {
+            assert!(!order.id.is_empty(), "Order ID should not be empty");
+            assert!(!order.sn.is_empty(), "SN should not be empty");
+            assert!(!order.market_symbol.is_empty(), "Market symbol should not be empty");
+            assert!([Side::Buy, Side::Sell].contains(&order.side), "Side should be either BUY or SELL");
+            assert!([OrderType::Limit, OrderType::Market].contains(&order.r#type), "Type should be either LIMIT or MARKET");
+            assert!(!matches!(order.state, OrderState::Unknown(_)), "State should be a known state");
+            assert!(order.price_avg.to_string().parse::<f64>().is_ok(), "Price avg should be a valid number");
+            assert!(order.quantity.to_string().parse::<f64>().is_ok(), "Quantity should be a valid number");
+            assert!(order.quantity_executed.to_string().parse::<f64>().is_ok(), "Quantity executed should be a valid number");
+            assert!(order.created_at.ends_with('Z'), "Created_at should end with 'Z' for UTC format");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_order_by_id() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/orders/by-order-id/1234567890"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "id": "1234567890",
+                    "sn": "OKMAKSDHRVVREK",
+                    "client_order_id": "451637946501",
+                    "market_symbol": "btcbrl",
+                    "side": "BUY",
+                    "type": "LIMIT",
+                    "state": "ACTIVE",
+                    "price": "290000.0",
+                    "price_avg": "295333.3333",
+                    "quantity": "0.42",
+                    "quantity_executed": "0.41",
+                    "instant_amount": "290.0",
+                    "instant_amount_executed": "290.0",
+                    "created_at": "2021-02-15T22:06:32.999Z",
+                    "trades_count": 2,
+                    "remark": "A remarkable note for the order.",
+                    "funds_received": "290.0"
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit.get_order_by_id("1234567890").await;
+        assert!(result.is_ok());
+
+        let order = result.unwrap();
+
+        assert_eq!(order.id, "1234567890", "Order ID should match the requested ID");
+        assert!(!order.sn.is_empty(), "SN should not be empty");
+        assert!(!order.market_symbol.is_empty(), "Market symbol should not be empty");
+        assert!(order.client_order_id.is_some(), "Client order ID should be present");
+        assert!([Side::Buy, Side::Sell].contains(&order.side), "Side should be either BUY or SELL");
+        assert!([OrderType::Limit, OrderType::Market].contains(&order.r#type), "Type should be either LIMIT or MARKET");
+        assert!(!matches!(order.state, OrderState::Unknown(_)), "State should be a known state");
+
+        assert!(order.price.as_ref().map(|p| p.to_string().parse::<f64>().is_ok()).unwrap_or(true), "Price should be a valid number if present");
+        assert!(order.price_avg.to_string().parse::<f64>().is_ok(), "Price avg should be a valid number");
+        assert!(order.quantity.to_string().parse::<f64>().is_ok(), "Quantity should be a valid number");
+        assert!(order.quantity_executed.to_string().parse::<f64>().is_ok(), "Quantity executed should be a valid number");
+
+        assert!(order.instant_amount.as_ref().map(|p| p.to_string().parse::<f64>().is_ok()).unwrap_or(true), "Instant amount should be a valid number if present");
+        assert!(order.instant_amount_executed.as_ref().map(|p| p.to_string().parse::<f64>().is_ok()).unwrap_or(true), "Instant amount executed should be a valid number if present");
+
+        assert!(order.created_at.ends_with('Z'), "Created_at should end with 'Z' for UTC format");
+        assert!(order.trades_count >= 0, "Trades count should be a non-negative integer");
+        assert!(order.cancellation_reason.is_none(), "Cancellation reason should be None for active orders");
+    }
+
+    #[tokio::test]
+    async fn test_get_order_by_client_id() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/orders/by-client-order-id/451637946501"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "id": "1234567890",
+                    "sn": "OKMAKSDHRVVREK",
+                    "client_order_id": "451637946501",
+                    "market_symbol": "btcbrl",
+                    "side": "BUY",
+                    "type": "LIMIT",
+                    "state": "ACTIVE",
+                    "price": "290000.0",
+                    "price_avg": "295333.3333",
+                    "quantity": "0.42",
+                    "quantity_executed": "0.41",
+                    "instant_amount": "290.0",
+                    "instant_amount_executed": "290.0",
+                    "created_at": "2021-02-15T22:06:32.999Z",
+                    "trades_count": 2,
+                    "remark": "A remarkable note for the order.",
+                    "funds_received": "290.0"
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit.get_order_by_client_id("451637946501").await;
+        assert!(result.is_ok());
+
+        let order = result.unwrap();
+
+        assert_eq!(order.client_order_id.as_ref().unwrap(), "451637946501", "Client order ID should match the requested ID");
+        assert!(!order.id.is_empty(), "Order ID should not be empty");
+        assert!(!order.sn.is_empty(), "SN should not be empty");
+        assert!(!order.market_symbol.is_empty(), "Market symbol should not be empty");
+
+        assert!([Side::Buy, Side::Sell].contains(&order.side), "Side should be either BUY or SELL");
+        assert!([OrderType::Limit, OrderType::Market].contains(&order.r#type), "Type should be either LIMIT or MARKET");
+        assert!(!matches!(order.state, OrderState::Unknown(_)), "State should be a known state");
+
+        assert!(order.price.as_ref().map(|p| p.to_string().parse::<f64>().is_ok()).unwrap_or(true), "Price should be a valid number if present");
+        assert!(order.price_avg.to_string().parse::<f64>().is_ok(), "Price avg should be a valid number");
+        assert!(order.quantity.to_string().parse::<f64>().is_ok(), "Quantity should be a valid number");
+        assert!(order.quantity_executed.to_string().parse::<f64>().is_ok(), "Quantity executed should be a valid number");
+
+        assert!(order.instant_amount.as_ref().map(|p| p.to_string().parse::<f64>().is_ok()).unwrap_or(true), "Instant amount should be a valid number if present");
+        assert!(order.instant_amount_executed.as_ref().map(|p| p.to_string().parse::<f64>().is_ok()).unwrap_or(true), "Instant amount executed should be a valid number if present");
+
+        assert!(order.created_at.ends_with('Z'), "Created_at should end with 'Z' for UTC format");
+        assert!(order.trades_count >= 0, "Trades count should be a non-negative integer");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_orders() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("PUT"))
+            .and(path("/orders/cancel"))
+            .and(body_json(json!({ "type": "ALL" })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "sn": "OKMAKSDHRVVREK",
+                            "id": 123456789
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit.cancel_orders(CancelMode::All).await;
+        assert!(result.is_ok());
+
+        let cancel_order_response = result.unwrap();
+        assert!(!cancel_order_response.is_empty(), "Cancel order response should not be empty");
+
+        for order in cancel_order_response {
+            assert!(!order.sn.is_empty(), "SN should not be empty");
+            assert!(order.id > 0, "Order ID should be a positive integer");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_orders_targeted() {
+        let cases = vec![
+            (CancelMode::id(123456789), json!({ "type": "ID", "id": 123456789 })),
+            (
+                CancelMode::client_order_id("451637946501"),
+                json!({ "type": "CLIENT_ORDER_ID", "client_order_id": "451637946501" }),
+            ),
+            (
+                CancelMode::market("btcbrl"),
+                json!({ "type": "MARKET", "market_symbol": "btcbrl" }),
+            ),
+        ];
+
+        for (mode, expected_body) in cases {
+            let mock_server = MockServer::start().await;
+
+            Mock::given(method("PUT"))
+                .and(path("/orders/cancel"))
+                .and(body_json(expected_body.clone()))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(json!({
+                        "data": [
+                            {
+                                "sn": "OKMAKSDHRVVREK",
+                                "id": 123456789
+                            }
+                        ]
+                    }))
+                        .insert_header("content-type", "application/json"),
+                )
+                .mount(&mock_server)
+                .await;
+
+            let api_url = mock_server.uri();
+            let client: Client = Client::new();
+            let foxbit = Foxbit::new(client, api_url, credentials());
+
+            let result = foxbit.cancel_orders(mode).await;
+            assert!(result.is_ok(), "Cancel request should match {}", expected_body);
+
+            let cancel_order_response = result.unwrap();
+            assert_eq!(cancel_order_response.len(), 1);
+            assert_eq!(cancel_order_response[0].id, 123456789);
+        }
+    }
+
+    #[test]
+    fn test_cancel_mode_from_order_ref() {
+        assert_eq!(CancelMode::from(OrderRef::id(1)), CancelMode::id(1));
+        assert_eq!(
+            CancelMode::from(OrderRef::client_order_id("abc")),
+            CancelMode::client_order_id("abc")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_replace() {
+        let mock_server = MockServer::start().await;
+        mount_btcbrl_market(&mock_server).await;
+
+        Mock::given(method("GET"))
+            .and(path("/orders/by-client-order-id/451637946501"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "id": "1234567890",
+                    "sn": "OKMAKSDHRVVREK",
+                    "client_order_id": "451637946501",
+                    "market_symbol": "btcbrl",
+                    "side": "BUY",
+                    "type": "LIMIT",
+                    "state": "ACTIVE",
+                    "price": "290000.0",
+                    "price_avg": "0.0",
+                    "quantity": "0.42",
+                    "quantity_executed": "0.0",
+                    "created_at": "2021-02-15T22:06:32.999Z",
+                    "trades_count": 0
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/orders/cancel-replace"))
+            .and(body_json(json!({
+                "mode": "STRICT",
+                "cancel": {
+                    "type": "CLIENT_ORDER_ID",
+                    "client_order_id": "451637946501"
+                },
+                "create": {
+                    "side": "BUY",
+                    "type": "LIMIT",
+                    "market_symbol": "btcbrl",
+                    "quantity": "0.42000",
+                    "price": "291000.00",
+                    "client_order_id": "451637946501"
+                }
+            })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "cancel": {
+                        "id": 1234567890,
+                        "sn": "OKMAKSDHRVVREK"
+                    },
+                    "create": {
+                        "id": 1234567891,
+                        "sn": "OKMAKSDHRVVREL",
+                        "client_order_id": "451637946501"
+                    }
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit
+            .cancel_and_replace(OrderRef::client_order_id("451637946501"), Some(num("291000.0")), None)
+            .await;
+        assert!(result.is_ok(), "Cancel and replace should succeed: {:?}", result);
+
+        let response = result.unwrap();
+        assert_eq!(response.cancel.id, 1234567890);
+        assert_eq!(response.cancel.sn, "OKMAKSDHRVVREK");
+        assert_eq!(response.create.id, 1234567891);
+        assert_eq!(response.create.sn, "OKMAKSDHRVVREL");
+        assert_eq!(response.create.client_order_id, "451637946501");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_replace_rejects_orders_that_cannot_be_rebuilt() {
+        // Market orders never rest on the book, and the stop price of stop
+        // orders is not reported.
+        for order_type in ["MARKET", "STOP_LIMIT"] {
+            let mock_server = MockServer::start().await;
+
+            Mock::given(method("GET"))
+                .and(path("/orders/by-order-id/1234567890"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(json!({
+                        "id": "1234567890",
+                        "sn": "OKMAKSDHRVVREK",
+                        "market_symbol": "btcbrl",
+                        "side": "SELL",
+                        "type": order_type,
+                        "state": "ACTIVE",
+                        "price": "280000.0",
+                        "price_avg": "0.0",
+                        "quantity": "0.42",
+                        "quantity_executed": "0.0",
+                        "created_at": "2021-02-15T22:06:32.999Z",
+                        "trades_count": 0
+                    }))
+                        .insert_header("content-type", "application/json"),
+                )
+                .mount(&mock_server)
+                .await;
+
+            let foxbit = Foxbit::new(Client::new(), mock_server.uri(), credentials());
+
+            let result = foxbit
+                .cancel_and_replace(OrderRef::id(1234567890), Some(num("279000.0")), None)
+                .await;
+            assert!(
+                matches!(result, Err(FoxbitError::Validation(_))),
+                "{} orders should be rejected: {:?}",
+                order_type,
+                result
+            );
+
+            let requests = mock_server.received_requests().await.unwrap();
+            assert!(
+                requests.iter().all(|request| request.url.path() != "/orders/cancel-replace"),
+                "Nothing should be replaced"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_replace_partially_filled() {
+        let mock_server = MockServer::start().await;
+        mount_btcbrl_market(&mock_server).await;
+
+        Mock::given(method("GET"))
+            .and(path("/orders/by-order-id/1234567890"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "id": "1234567890",
+                    "sn": "OKMAKSDHRVVREK",
+                    "market_symbol": "btcbrl",
+                    "side": "BUY",
+                    "type": "LIMIT",
+                    "state": "PARTIALLY_FILLED",
+                    "price": "290000.0",
+                    "price_avg": "290000.0",
+                    "quantity": "0.42",
+                    "quantity_executed": "0.3",
+                    "created_at": "2021-02-15T22:06:32.999Z",
+                    "trades_count": 1
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/orders/cancel-replace"))
+            .and(body_json(json!({
+                "mode": "STRICT",
+                "cancel": {
+                    "type": "ID",
+                    "id": 1234567890
+                },
+                "create": {
+                    "side": "BUY",
+                    "type": "LIMIT",
+                    "market_symbol": "btcbrl",
+                    "quantity": "0.10000",
+                    "price": "291000.00"
+                }
+            })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "cancel": {
+                        "id": 1234567890,
+                        "sn": "OKMAKSDHRVVREK"
+                    },
+                    "create": {
+                        "id": 1234567891,
+                        "sn": "OKMAKSDHRVVREL",
+                        "client_order_id": ""
+                    }
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let foxbit = Foxbit::new(Client::new(), mock_server.uri(), credentials());
+
+        // Re-posting the full 0.42 would buy the filled 0.3 again.
+        let result = foxbit
+            .cancel_and_replace(OrderRef::id(1234567890), Some(num("291000.0")), None)
+            .await;
+        assert!(matches!(result, Err(FoxbitError::Validation(_))), "{:?}", result);
+
+        let result = foxbit
+            .cancel_and_replace(OrderRef::id(1234567890), None, Some(num("0.2")))
+            .await;
+        assert!(matches!(result, Err(FoxbitError::Validation(_))), "{:?}", result);
+
+        let response = foxbit
+            .cancel_and_replace(OrderRef::id(1234567890), Some(num("291000.0")), Some(num("0.1")))
+            .await
+            .unwrap();
+        assert_eq!(response.create.id, 1234567891);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_replace_rejects_filled_orders() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/orders/by-order-id/1234567890"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "id": "1234567890",
+                    "sn": "OKMAKSDHRVVREK",
+                    "market_symbol": "btcbrl",
+                    "side": "BUY",
+                    "type": "LIMIT",
+                    "state": "FILLED",
+                    "price": "290000.0",
+                    "price_avg": "290000.0",
+                    "quantity": "0.42",
+                    "quantity_executed": "0.420",
+                    "created_at": "2021-02-15T22:06:32.999Z",
+                    "trades_count": 2
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let foxbit = Foxbit::new(Client::new(), mock_server.uri(), credentials());
+
+        let result = foxbit
+            .cancel_and_replace(OrderRef::id(1234567890), Some(num("291000.0")), None)
+            .await;
+        assert!(matches!(result, Err(FoxbitError::Validation(_))), "{:?}", result);
+
+        let requests = mock_server.received_requests().await.unwrap();
+        assert!(
+            requests.iter().all(|request| request.url.path() != "/orders/cancel-replace"),
+            "Nothing should be replaced"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_replace_with() {
+        let mock_server = MockServer::start().await;
+        mount_btcbrl_market(&mock_server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/orders/cancel-replace"))
+            .and(body_json(json!({
+                "mode": "STRICT",
+                "cancel": {
+                    "type": "ID",
+                    "id": 1234567890
+                },
+                "create": {
+                    "side": "BUY",
+                    "type": "LIMIT",
+                    "market_symbol": "btcbrl",
+                    "quantity": "0.42000",
+                    "price": "291000.00",
+                    "post_only": true,
+                    "client_order_id": "quote-1"
+                }
+            })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "cancel": {
+                        "id": 1234567890,
+                        "sn": "OKMAKSDHRVVREK"
+                    },
+                    "create": {
+                        "id": 1234567891,
+                        "sn": "OKMAKSDHRVVREL",
+                        "client_order_id": "quote-1"
+                    }
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let foxbit = Foxbit::new(Client::new(), mock_server.uri(), credentials());
+
+        let replacement = OrderRequest::limit(Side::Buy, "btcbrl")
+            .quantity(num("0.42"))
+            .price(num("291000"))
+            .post_only()
+            .client_order_id("quote-1")
+            .build()
+            .unwrap();
+        let response = foxbit
+            .cancel_and_replace_with(OrderRef::id(1234567890), &replacement)
+            .await
+            .unwrap();
+        assert_eq!(response.create.client_order_id, "quote-1");
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_replace_requires_changes() {
+        let mock_server = MockServer::start().await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit.cancel_and_replace(OrderRef::id(1234567890), None, None).await;
+        assert!(matches!(result, Err(FoxbitError::Validation(_))));
+
+        let requests = mock_server.received_requests().await.unwrap();
+        assert!(requests.is_empty(), "Nothing should be sent");
+    }
+
+    #[tokio::test]
+    async fn test_list_trades() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/trades"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [{
+                        "id": 1234567890,
+                        "sn": "TC5JZVW2LLJ3IW",
+                        "order_id": "1234567890",
+                        "market_symbol": "btcbrl",
+                        "side": "BUY",
+                        "price": "290000.0",
+                        "quantity": "1.0",
+                        "fee": "0.01",
+                        "fee_currency_symbol": "btc",
+                        "created_at": "2021-02-15T22:06:32.999Z",
+                        "role": "TAKER",
+                    }]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = match env::var("API_ENV") {
+            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
+            _ => mock_server.uri(),
+        };
+
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit
+            .list_trades(
+                "2024-08-28T00:00:00.000Z", // start_time
+                "2024-08-29T20:00:22.013Z", // end_time
+                10,                         // page_size
+                1,                          // page
+                "btcbrl",                   // market_symbol
+            )
+            .await;
+
+        assert!(result.is_ok());
+
+        let listed_trades = result.unwrap();
+        assert!(!listed_trades.is_empty(), "Trades list should not be empty");
+
+        for trade in listed_trades {
+            assert!(trade.id > 0, "Trade ID should be a positive integer");
+            assert!(!trade.sn.is_empty(), "SN should not be empty");
+            assert!(!trade.order_id.is_empty(), "Order ID should not be empty");
+            assert!(!trade.market_symbol.is_empty(), "Market symbol should not be empty");
+            assert!([Side::Buy, Side::Sell].contains(&trade.side), "Side should be either BUY or SELL");
+
+            assert!(trade.price.to_string().parse::<f64>().is_ok(), "Price should be a valid number");
+            assert!(trade.quantity.to_string().parse::<f64>().is_ok(), "Quantity should be a valid number");
+            assert!(trade.fee.to_string().parse::<f64>().is_ok(), "Fee should be a valid number");
+
+            assert!(!trade.fee_currency_symbol.is_empty(), "Fee currency symbol should not be empty");
+            assert!(trade.created_at.ends_with('Z'), "Created_at should end with 'Z' for UTC format");
+            assert!([TradeRole::Taker, TradeRole::Maker].contains(&trade.role), "Role should be either TAKER or MAKER");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_accounts() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/accounts"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "currency_symbol": "btc",
+                            "balance": "1.0",
+                            "balance_available": "0.9",
+                            "balance_locked": "0.1"
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit.list_accounts().await;
+        assert!(result.is_ok());
+
+        let accounts = result.unwrap();
+        assert!(!accounts.is_empty(), "Accounts list should not be empty");
+
+        for account in accounts {
+            assert!(!account.currency_symbol.is_empty(), "Currency symbol should not be empty");
+            let total = account.balance.to_string().parse::<f64>().expect("Balance should be a valid number");
+            let available = account.balance_available.to_string().parse::<f64>().expect("Available balance should be a valid number");
+            let locked = account.balance_locked.to_string().parse::<f64>().expect("Locked balance should be a valid number");
+            assert!((available + locked - total).abs() < 1e-9, "Available and locked should add up to the balance");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_value_portfolio() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/accounts"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        { "currency_symbol": "brl", "balance": "1000.0", "balance_available": "1000.0", "balance_locked": "0" },
+                        { "currency_symbol": "btc", "balance": "0.5", "balance_available": "0.5", "balance_locked": "0" },
+                        { "currency_symbol": "eth", "balance": "0", "balance_available": "0", "balance_locked": "0" },
+                        { "currency_symbol": "xyz", "balance": "3", "balance_available": "3", "balance_locked": "0" }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/markets"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "symbol": "btcbrl",
+                            "quantity_min": "0.00001",
+                            "quantity_increment": "0.00001",
+                            "price_min": "1.0",
+                            "price_increment": "0.01",
+                            "base": { "symbol": "btc", "precision": 8 },
+                            "quote": { "symbol": "brl", "precision": 2 }
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/markets/ticker/24hr"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "market_symbol": "btcbrl",
+                            "last_trade": { "price": "300000.0", "volume": "0.001", "date": "2024-01-01T00:00:00.000Z" },
+                            "rolling_24h": {
+                                "price_change": "0", "price_change_percent": "0", "volume": "1",
+                                "trades_count": 1, "open": "300000.0", "high": "300000.0", "low": "300000.0"
+                            },
+                            "best": {}
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit.value_portfolio("brl").await;
+        assert!(result.is_ok(), "Valuation should succeed: {:?}", result);
+
+        let valuation = result.unwrap();
+        assert_eq!(valuation.quote_currency, "brl");
+        assert_eq!(valuation.holdings.len(), 2, "Empty balances should be skipped");
+        assert!((to_f64(valuation.total) - 151000.0).abs() < 1e-6, "Total should be 1000 BRL + 0.5 BTC");
+        assert_eq!(valuation.unpriced, vec!["xyz".to_string()]);
+
+        let btc = valuation.holdings.iter().find(|h| h.currency_symbol == "btc").unwrap();
+        assert_eq!(to_f64(btc.price), 300000.0);
+        assert_eq!(to_f64(btc.value), 150000.0);
+    }
+
+    #[tokio::test]
+    async fn test_list_deposits() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/deposits"))
+            .and(query_param("state", "ACCEPTED"))
+            .and(query_param("currency_symbol", "btc"))
+            .and(query_param("page_size", "10"))
+            .and(query_param("page", "1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "sn": "DPBRL5GSAJZ3DS",
+                            "state": "ACCEPTED",
+                            "currency_symbol": "btc",
+                            "amount": "0.5",
+                            "fee": "0",
+                            "created_at": "2024-08-28T10:00:00.000Z",
+                            "details_crypto": {
+                                "network": { "name": "Bitcoin", "code": "btc" },
+                                "transaction_id": "0x3f4a",
+                                "receiving_address": "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh",
+                                "confirmations": 3
+                            }
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let filter = DepositFilter {
+            state: Some("ACCEPTED".into()),
+            currency_symbol: Some("btc".into()),
+            page_size: Some(10),
+            page: Some(1),
+            ..Default::default()
+        };
+        let result = foxbit.list_deposits(&filter).await;
+        assert!(result.is_ok());
+
+        let deposits = result.unwrap();
+        assert!(!deposits.is_empty(), "Deposits list should not be empty");
+
+        let deposit_info: DepositInfo = serde_json::from_value(json!({ "min_to_confirm": "6" })).unwrap();
+        for deposit in deposits {
+            assert!(!deposit.sn.is_empty(), "SN should not be empty");
+            assert!(deposit.amount.to_string().parse::<f64>().is_ok(), "Amount should be a valid number");
+            assert!(deposit.created_at.ends_with('Z'), "Created_at should end with 'Z' for UTC format");
+            assert_eq!(deposit.confirmations_remaining(&deposit_info), Some(3));
+            assert_eq!(deposit.is_confirmed(&deposit_info), Some(false));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_deposit() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/deposits/DPBRL5GSAJZ3DS"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "sn": "DPBRL5GSAJZ3DS",
+                    "state": "ACCEPTED",
+                    "currency_symbol": "brl",
+                    "amount": "100.0",
+                    "created_at": "2024-08-28T10:00:00.000Z"
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit.get_deposit("DPBRL5GSAJZ3DS").await;
+        assert!(result.is_ok());
+
+        let deposit = result.unwrap();
+        assert_eq!(deposit.sn, "DPBRL5GSAJZ3DS", "SN should match the requested deposit");
+        assert!(deposit.details_crypto.is_none(), "Fiat deposits should have no crypto details");
+
+        let deposit_info: DepositInfo = serde_json::from_value(json!({ "min_to_confirm": "6" })).unwrap();
+        assert_eq!(deposit.is_confirmed(&deposit_info), None, "Fiat deposits have no confirmations");
+    }
+
+    #[tokio::test]
+    async fn test_get_deposit_address() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/deposits/address"))
+            .and(query_param("currency_symbol", "xrp"))
+            .and(query_param("network_code", "ripple"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "currency_symbol": "xrp",
+                    "address": "rLHzPsX6oXkzU2qL12kHCH8G8cnZv1rBJh",
+                    "destination_tag": "123456",
+                    "network": { "name": "Ripple", "code": "ripple" }
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit.get_deposit_address("xrp", Some("ripple")).await;
+        assert!(result.is_ok());
+
+        let deposit_address = result.unwrap();
+        assert_eq!(deposit_address.currency_symbol, "xrp");
+        assert!(!deposit_address.address.is_empty(), "Address should not be empty");
+        assert_eq!(deposit_address.destination_tag.as_deref(), Some("123456"));
+        assert!(deposit_address.requires_memo(), "XRP deposits should require a destination tag");
+        assert_eq!(deposit_address.network.unwrap().code, "ripple");
+    }
+
+    #[tokio::test]
+    async fn test_list_withdrawals() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/withdrawals"))
+            .and(query_param("currency_symbol", "btc"))
+            .and(query_param("page_size", "10"))
+            .and(query_param("page", "1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "sn": "WDBTC7NQ2KX4PL",
+                            "state": "CONFIRMED",
+                            "currency_symbol": "btc",
+                            "amount": "0.1",
+                            "fee": "0.0001",
+                            "created_at": "2024-08-28T10:00:00.000Z",
+                            "details_crypto": {
+                                "network": { "name": "Bitcoin", "code": "btc" },
+                                "transaction_id": "0x3f4a",
+                                "destination_address": "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh"
+                            }
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let filter = WithdrawalFilter {
+            currency_symbol: Some("btc".into()),
+            page_size: Some(10),
+            page: Some(1),
+            ..Default::default()
+        };
+        let result = foxbit.list_withdrawals(&filter).await;
+        assert!(result.is_ok());
+
+        let withdrawals = result.unwrap();
+        assert!(!withdrawals.is_empty(), "Withdrawals list should not be empty");
+
+        for withdrawal in withdrawals {
+            assert!(!withdrawal.sn.is_empty(), "SN should not be empty");
+            assert!(withdrawal.amount.to_string().parse::<f64>().is_ok(), "Amount should be a valid number");
+            assert!(withdrawal.details_crypto.is_some(), "Crypto details should be present");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_withdrawal() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/withdrawals/WDBTC7NQ2KX4PL"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "sn": "WDBTC7NQ2KX4PL",
+                    "state": "PROCESSING",
+                    "currency_symbol": "btc",
+                    "amount": "0.1",
+                    "created_at": "2024-08-28T10:00:00.000Z"
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit.get_withdrawal("WDBTC7NQ2KX4PL").await;
+        assert!(result.is_ok());
+
+        let withdrawal = result.unwrap();
+        assert_eq!(withdrawal.sn, "WDBTC7NQ2KX4PL", "SN should match the requested withdrawal");
+        assert_eq!(withdrawal.state, "PROCESSING");
+    }
+
+    async fn mount_withdrawable_currency(mock_server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/currencies"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "symbol": "btc",
+                            "name": "Bitcoin",
+                            "type": "crypto",
+                            "precision": 8,
+                            "withdraw_info": {
+                                "enabled": true,
+                                "min_amount": "0.001",
+                                "fee": "0.0005"
+                            }
+                        },
+                        {
+                            "symbol": "eth",
+                            "name": "Ethereum",
+                            "type": "crypto",
+                            "precision": 8,
+                            "withdraw_info": { "enabled": false }
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(mock_server)
+            .await;
+        mount_empty_list(mock_server, "/markets").await;
+    }
+
+    #[tokio::test]
+    async fn test_create_withdrawal() {
+        let mock_server = MockServer::start().await;
+        mount_withdrawable_currency(&mock_server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/withdrawals"))
+            .and(body_json(json!({
+                "currency_symbol": "btc",
+                "amount": "0.01",
+                "destination_crypto": {
+                    "address": "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh",
+                    "network_code": "btc"
+                }
+            })))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .set_body_json(json!({
+                    "sn": "WDBTC7NQ2KX4PL",
+                    "state": "PENDING",
+                    "currency_symbol": "btc",
+                    "amount": "0.01",
+                    "fee": "0.0005",
+                    "created_at": "2024-08-28T10:00:00.000Z"
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let mut request = WithdrawalRequest::crypto(
+            "btc",
+            num("0.01"),
+            "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh",
+        );
+        if let Some(destination) = request.destination_crypto.as_mut() {
+            destination.network_code = Some("btc".into());
+        }
+        let result = foxbit.create_withdrawal(&request).await;
+        assert!(result.is_ok());
+
+        let withdrawal = result.unwrap();
+        assert_eq!(withdrawal.sn, "WDBTC7NQ2KX4PL");
+        assert_eq!(withdrawal.fee, Some(num("0.0005")));
+    }
+
+    #[tokio::test]
+    async fn test_create_withdrawal_preflight() {
+        let mock_server = MockServer::start().await;
+        mount_withdrawable_currency(&mock_server).await;
+
+        Mock::given(method("POST"))
+            .and(path("/withdrawals"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let address = "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh";
+        for request in [
+            // Below min_amount
+            WithdrawalRequest::crypto("btc", num("0.0001"), address),
+            // Above min_amount but not above the fee
+            WithdrawalRequest::crypto("btc", num("0.0005"), address),
+            // No destination
+            WithdrawalRequest {
+                currency_symbol: "btc".into(),
+                amount: num("0.01"),
+                destination_crypto: None,
+                destination_bank_account: None,
+            },
+            // Two destinations
+            WithdrawalRequest {
+                destination_bank_account: Some(BankAccount::new(
+                    341,
+                    "0001",
+                    "12345-6",
+                    BankAccountType::Checking,
+                    "529.982.247-25",
+                )),
+                ..WithdrawalRequest::crypto("btc", num("0.01"), address)
+            },
+            // Withdrawals disabled
+            WithdrawalRequest::crypto("eth", num("1"), address),
+            // Unknown currency
+            WithdrawalRequest::crypto("doge", num("100"), address),
+        ] {
+            let result = foxbit.create_withdrawal(&request).await;
+            assert!(
+                matches!(result, Err(FoxbitError::Validation(_))),
+                "{} {} should be rejected locally",
+                request.amount,
+                request.currency_symbol
+            );
+        }
+
+        let info: WithdrawInfo = serde_json::from_value(json!({
+            "enabled": true,
+            "min_amount": "0.0001",
+            "fee": "0.00005"
+        }))
+        .unwrap();
+        assert!(info.check_amount(&num("0.0005")).is_ok());
+        assert!(matches!(info.check_amount(&num("-1")), Err(FoxbitError::Validation(_))));
+    }
+
+    #[test]
+    fn test_validate_document() {
+        assert_eq!(validate_document("529.982.247-25").unwrap(), DocumentKind::Cpf);
+        assert_eq!(validate_document("52998224725").unwrap(), DocumentKind::Cpf);
+        assert_eq!(validate_document("11.222.333/0001-81").unwrap(), DocumentKind::Cnpj);
+
+        for invalid in ["529.982.247-24", "111.111.111-11", "11.222.333/0001-80", "1234", "abc"] {
+            assert!(
+                matches!(validate_document(invalid), Err(FoxbitError::Validation(_))),
+                "{} should be rejected",
+                invalid
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_fiat_withdrawal() {
+        let mock_server = MockServer::start().await;
+        mount_empty_list(&mock_server, "/markets").await;
+
+        Mock::given(method("GET"))
+            .and(path("/currencies"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "symbol": "brl",
+                            "name": "Real",
+                            "type": "fiat",
+                            "precision": 2,
+                            "withdraw_info": {
+                                "enabled": true,
+                                "min_amount": "10",
+                                "fee": "3.5"
+                            }
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/banks"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        { "abbreviation": "BB", "name": "Banco do Brasil S.A.", "code": 1 },
+                        { "abbreviation": "ITAU", "name": "Itaú Unibanco S.A.", "code": 341 }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/withdrawals"))
+            .and(body_json(json!({
+                "currency_symbol": "brl",
+                "amount": "150.00",
+                "destination_bank_account": {
+                    "bank_code": 341,
+                    "branch": "0001",
+                    "account": "12345-6",
+                    "account_type": "CHECKING",
+                    "holder_document": "52998224725"
+                }
+            })))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .set_body_json(json!({
+                    "sn": "WDBRL3MZ8RT1QA",
+                    "state": "PENDING",
+                    "currency_symbol": "brl",
+                    "amount": "150.00",
+                    "fee": "3.5",
+                    "created_at": "2024-08-28T10:00:00.000Z"
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let bank_account = BankAccount::new(
+            341,
+            "0001",
+            "12345-6",
+            BankAccountType::Checking,
+            "529.982.247-25",
+        );
+        let result = foxbit.create_fiat_withdrawal(num("150.00"), bank_account.clone()).await;
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().sn, "WDBRL3MZ8RT1QA");
+
+        // A document set on the field directly is sent without punctuation too.
+        let bank_account = BankAccount {
+            holder_document: "529.982.247-25".into(),
+            ..bank_account
+        };
+        let result = foxbit.create_fiat_withdrawal(num("150.00"), bank_account).await;
+        assert!(result.is_ok(), "{:?}", result);
+
+        // Unknown bank code
+        let bank_account = BankAccount::new(
+            999,
+            "0001",
+            "12345-6",
+            BankAccountType::Checking,
+            "529.982.247-25",
+        );
+        let result = foxbit.create_fiat_withdrawal(num("150.00"), bank_account).await;
+        assert!(matches!(result, Err(FoxbitError::Validation(_))));
+
+        // Wrong check digits on the holder document
+        let bank_account = BankAccount::new(
+            341,
+            "0001",
+            "12345-6",
+            BankAccountType::Savings,
+            "11.222.333/0001-80",
+        );
+        let result = foxbit.create_fiat_withdrawal(num("150.00"), bank_account).await;
+        assert!(matches!(result, Err(FoxbitError::Validation(_))));
+    }
+
+    async fn mount_transactional_limits(mock_server: &MockServer) {
+        Mock::given(method("GET"))
+            .and(path("/me/limits"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        {
+                            "currency_symbol": "brl",
+                            "type": "WITHDRAWAL",
+                            "interval": "DAILY",
+                            "level": 2,
+                            "amount": "50000",
+                            "used": "45000",
+                            "remaining": "5000"
+                        },
+                        {
+                            "currency_symbol": "brl",
+                            "type": "WITHDRAWAL",
+                            "interval": "MONTHLY",
+                            "level": 2,
+                            "amount": "200000",
+                            "used": "45000",
+                            "remaining": "155000"
+                        },
+                        {
+                            "currency_symbol": "brl",
+                            "type": "WITHDRAWAL",
+                            "interval": "DAILY",
+                            "level": 3,
+                            "amount": "500000",
+                            "used": "0",
+                            "remaining": "500000"
+                        },
+                        {
+                            "currency_symbol": "brl",
+                            "type": "DEPOSIT",
+                            "interval": "DAILY",
+                            "amount": "100000",
+                            "used": "0",
+                            "remaining": "100000"
+                        }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_list_transactional_limits() {
+        let mock_server = MockServer::start().await;
+        mount_transactional_limits(&mock_server).await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        let result = foxbit.list_transactional_limits().await;
+        assert!(result.is_ok());
+
+        let limits = result.unwrap();
+        assert_eq!(limits.len(), 4);
+        for limit in limits {
+            let amount = limit.amount.to_string().parse::<f64>().unwrap();
+            let used = limit.used.to_string().parse::<f64>().unwrap();
+            let remaining = limit.remaining.to_string().parse::<f64>().unwrap();
+            assert_eq!(amount - used, remaining, "Remaining should be the unused amount");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_transactional_limits() {
+        let mock_server = MockServer::start().await;
+        mount_transactional_limits(&mock_server).await;
+
+        Mock::given(method("GET"))
+            .and(path("/me"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "sn": "MB123",
+                    "email": "member@example.com",
+                    "level": 2,
+                    "created_at": "2024-08-28T10:00:00.000Z",
+                    "disabled": false
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        let api_url = mock_server.uri();
+        let client: Client = Client::new();
+        let foxbit = Foxbit::new(client, api_url, credentials());
+
+        // Over the level 2 daily limit only; the level 3 limit does not apply.
+        let warnings = foxbit
+            .check_transactional_limits("brl", LimitOperation::Withdrawal, num("10000"))
+            .await
+            .unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].interval, LimitInterval::Daily);
+        assert_eq!(warnings[0].remaining, num("5000"));
+
+        let warnings = foxbit
+            .check_transactional_limits("brl", LimitOperation::Withdrawal, num("1000"))
+            .await
+            .unwrap();
+        assert!(warnings.is_empty());
+
+        let warnings = foxbit
+            .check_transactional_limits("brl", LimitOperation::Deposit, num("10000"))
+            .await
+            .unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_otc_markets() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/otc/markets"))
+            .and(header_exists("X-FB-ACCESS-SIGNATURE"))
             .respond_with(
                 ResponseTemplate::new(200)
                     .set_body_json(json!({
                     "data": [
                         {
-                            "id": "1234567890",
-                            "sn": "OKMAKSDHRVVREK",
-                            "client_order_id": "451637946501",
                             "market_symbol": "btcbrl",
-                            "side": "BUY",
-                            "type": "LIMIT",
-                            "state": "ACTIVE",
-                            "price": "290000.0",
-                            "price_avg": "295333.3333",
-                            "quantity": "0.42",
-                            "quantity_executed": "0.41",
-                            "instant_amount": "290.0",
-                            "instant_amount_executed": "290.0",
-                            "created_at": "2021-02-15T22:06:32.999Z",
-                            "trades_count": 2,
-                            "remark": "A remarkable note for the order.",
-                            "funds_received": "290.0"
+                            "base_currency_symbol": "btc",
+                            "quote_currency_symbol": "brl",
+                            "quantity_min": "0.1",
+                            "quantity_max": "50"
                         }
                     ]
                 }))
@@ -518,134 +3165,129 @@ mod tests {
             .mount(&mock_server)
             .await;
 
-        let api_url = match env::var("API_ENV") {
-            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
-            _ => mock_server.uri(),
-        };
-
+        let api_url = mock_server.uri();
         let client: Client = Client::new();
-        let foxbit = Foxbit::new(client, api_url);
-
-        let result = foxbit
-            .list_orders(
-                "2024-08-28T00:00:00.000Z",
-                "2024-08-29T20:00:22.013Z",
-                10,
-                1,
-                "btcbrl",
-                "FILLED",
-                "BUY",
-            )
-            .await;
+        let foxbit = Foxbit::new(client, api_url, credentials());
 
+        let result = foxbit.list_otc_markets().await;
         assert!(result.is_ok());
 
-        let orders = result.unwrap();
-        assert!(!orders.is_empty(), "Orders list should not be empty");
-
-        for order in orders {
-            assert!(!order.id.is_empty(), "Order ID should not be empty");
-            assert!(!order.sn.is_empty(), "SN should not be empty");
-            assert!(!order.market_symbol.is_empty(), "Market symbol should not be empty");
-            assert!(["BUY", "SELL"].contains(&order.side.as_str()), "Side should be either BUY or SELL");
-            assert!(["LIMIT", "MARKET"].contains(&order.r#type.as_str()), "Type should be either LIMIT or MARKET");
-            assert!(!order.state.is_empty(), "State should not be empty");
-            assert!(order.price_avg.parse::<f64>().is_ok(), "Price avg should be a valid number");
-            assert!(order.quantity.parse::<f64>().is_ok(), "Quantity should be a valid number");
-            assert!(order.quantity_executed.parse::<f64>().is_ok(), "Quantity executed should be a valid number");
-            assert!(order.created_at.ends_with('Z'), "Created_at should end with 'Z' for UTC format");
-        }
+        let otc_markets = result.unwrap();
+        assert_eq!(otc_markets.len(), 1);
+        assert_eq!(otc_markets[0].market_symbol, "btcbrl");
+        assert_eq!(otc_markets[0].quantity_max, Some(num("50")));
     }
 
     #[tokio::test]
-    async fn test_get_order_by_id() {
+    async fn test_request_and_execute_otc_quote() {
         let mock_server = MockServer::start().await;
+        let expires_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64
+            + 30_000;
 
-        Mock::given(method("GET"))
-            .and(path("/orders/by-order-id/1234567890"))
+        Mock::given(method("POST"))
+            .and(path("/otc/quotes"))
+            .and(body_json(json!({
+                "side": "BUY",
+                "market_symbol": "btcbrl",
+                "quantity": "2"
+            })))
             .respond_with(
-                ResponseTemplate::new(200)
+                ResponseTemplate::new(201)
                     .set_body_json(json!({
-                    "id": "1234567890",
-                    "sn": "OKMAKSDHRVVREK",
-                    "client_order_id": "451637946501",
+                    "quote_id": "QT7Y2K",
+                    "side": "BUY",
                     "market_symbol": "btcbrl",
+                    "price": "350000.00",
+                    "quantity": "2",
+                    "amount": "700000.00",
+                    "created_at": "2024-08-28T10:00:00.000Z",
+                    "expires_at": expires_at
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(path("/otc/quotes/QT7Y2K/execute"))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .set_body_json(json!({
+                    "sn": "OTC9F3D1",
+                    "quote_id": "QT7Y2K",
                     "side": "BUY",
-                    "type": "LIMIT",
-                    "state": "ACTIVE",
-                    "price": "290000.0",
-                    "price_avg": "295333.3333",
-                    "quantity": "0.42",
-                    "quantity_executed": "0.41",
-                    "instant_amount": "290.0",
-                    "instant_amount_executed": "290.0",
-                    "created_at": "2021-02-15T22:06:32.999Z",
-                    "trades_count": 2,
-                    "remark": "A remarkable note for the order.",
-                    "funds_received": "290.0"
+                    "market_symbol": "btcbrl",
+                    "price": "350000.00",
+                    "quantity": "2",
+                    "amount": "700000.00",
+                    "created_at": "2024-08-28T10:00:05.000Z"
                 }))
                     .insert_header("content-type", "application/json"),
             )
+            .expect(1)
             .mount(&mock_server)
             .await;
 
         let api_url = mock_server.uri();
-
         let client: Client = Client::new();
-        let foxbit = Foxbit::new(client, api_url);
-
-        let result = foxbit.get_order_by_id("1234567890").await;
-        assert!(result.is_ok());
-
-        let order = result.unwrap();
-
-        assert_eq!(order.id, "1234567890", "Order ID should match the requested ID");
-        assert!(!order.sn.is_empty(), "SN should not be empty");
-        assert!(!order.market_symbol.is_empty(), "Market symbol should not be empty");
-        assert!(order.client_order_id.is_some(), "Client order ID should be present");
-        assert!(["BUY", "SELL"].contains(&order.side.as_str()), "Side should be either BUY or SELL");
-        assert!(["LIMIT", "MARKET"].contains(&order.r#type.as_str()), "Type should be either LIMIT or MARKET");
-        assert!(!order.state.is_empty(), "State should not be empty");
+        let foxbit = Foxbit::new(client, api_url, credentials());
 
-        assert!(order.price.as_ref().map(|p| p.parse::<f64>().is_ok()).unwrap_or(true), "Price should be a valid number if present");
-        assert!(order.price_avg.parse::<f64>().is_ok(), "Price avg should be a valid number");
-        assert!(order.quantity.parse::<f64>().is_ok(), "Quantity should be a valid number");
-        assert!(order.quantity_executed.parse::<f64>().is_ok(), "Quantity executed should be a valid number");
-
-        assert!(order.instant_amount.as_ref().map(|p| p.parse::<f64>().is_ok()).unwrap_or(true), "Instant amount should be a valid number if present");
-        assert!(order.instant_amount_executed.as_ref().map(|p| p.parse::<f64>().is_ok()).unwrap_or(true), "Instant amount executed should be a valid number if present");
+        let request = OtcQuoteRequest {
+            side: Side::Buy,
+            market_symbol: "btcbrl".into(),
+            quantity: Some(num("2")),
+            amount: None,
+        };
+        let quote = foxbit.request_otc_quote(&request).await.unwrap();
+        assert_eq!(quote.expires_at, expires_at);
+        assert!(!quote.is_expired_at(foxbit.clock().now_millis()));
+
+        let execution = foxbit.execute_otc_quote(&quote).await.unwrap();
+        assert_eq!(execution.quote_id, quote.quote_id);
+        assert_eq!(execution.amount, num("700000.00"));
+
+        // An expired quote never reaches the desk.
+        let expired = OtcQuote {
+            expires_at: expires_at - 60_000,
+            ..quote
+        };
+        let result = foxbit.execute_otc_quote(&expired).await;
+        assert!(matches!(result, Err(FoxbitError::Validation(_))));
 
-        assert!(order.created_at.ends_with('Z'), "Created_at should end with 'Z' for UTC format");
-        assert!(order.trades_count >= 0, "Trades count should be a non-negative integer");
-        assert!(order.cancellation_reason.is_none(), "Cancellation reason should be None for active orders");
+        let missing_size = OtcQuoteRequest {
+            quantity: None,
+            ..request
+        };
+        let result = foxbit.request_otc_quote(&missing_size).await;
+        assert!(matches!(result, Err(FoxbitError::Validation(_))));
     }
 
     #[tokio::test]
-    async fn test_get_order_by_client_id() {
+    async fn test_list_otc_executions() {
         let mock_server = MockServer::start().await;
 
         Mock::given(method("GET"))
-            .and(path("/orders/by-client-order-id/451637946501"))
+            .and(path("/otc/executions"))
+            .and(query_param("market_symbol", "btcbrl"))
+            .and(query_param("page_size", "50"))
             .respond_with(
                 ResponseTemplate::new(200)
                     .set_body_json(json!({
-                    "id": "1234567890",
-                    "sn": "OKMAKSDHRVVREK",
-                    "client_order_id": "451637946501",
-                    "market_symbol": "btcbrl",
-                    "side": "BUY",
-                    "type": "LIMIT",
-                    "state": "ACTIVE",
-                    "price": "290000.0",
-                    "price_avg": "295333.3333",
-                    "quantity": "0.42",
-                    "quantity_executed": "0.41",
-                    "instant_amount": "290.0",
-                    "instant_amount_executed": "290.0",
-                    "created_at": "2021-02-15T22:06:32.999Z",
-                    "trades_count": 2,
-                    "remark": "A remarkable note for the order.",
-                    "funds_received": "290.0"
+                    "data": [
+                        {
+                            "sn": "OTC9F3D1",
+                            "quote_id": "QT7Y2K",
+                            "side": "BUY",
+                            "market_symbol": "btcbrl",
+                            "price": "350000.00",
+                            "quantity": "2",
+                            "amount": "700000.00",
+                            "created_at": "2024-08-28T10:00:05.000Z"
+                        }
+                    ]
                 }))
                     .insert_header("content-type", "application/json"),
             )
@@ -654,136 +3296,498 @@ mod tests {
 
         let api_url = mock_server.uri();
         let client: Client = Client::new();
-        let foxbit = Foxbit::new(client, api_url);
+        let foxbit = Foxbit::new(client, api_url, credentials());
 
-        let result = foxbit.get_order_by_client_id("451637946501").await;
+        let filter = OtcExecutionFilter {
+            market_symbol: Some("btcbrl".into()),
+            page_size: Some(50),
+            ..Default::default()
+        };
+        let result = foxbit.list_otc_executions(&filter).await;
         assert!(result.is_ok());
 
-        let order = result.unwrap();
+        let executions = result.unwrap();
+        assert!(!executions.is_empty(), "Executions list should not be empty");
+        for execution in executions {
+            assert_eq!(execution.market_symbol, "btcbrl");
+            assert!(!execution.quote_id.is_empty(), "Quote ID should not be empty");
+        }
+    }
 
-        assert_eq!(order.client_order_id.as_ref().unwrap(), "451637946501", "Client order ID should match the requested ID");
-        assert!(!order.id.is_empty(), "Order ID should not be empty");
-        assert!(!order.sn.is_empty(), "SN should not be empty");
-        assert!(!order.market_symbol.is_empty(), "Market symbol should not be empty");
+    #[test]
+    fn test_enum_fallbacks() {
+        assert_eq!(Side::from("buy"), Side::Buy);
+        assert_eq!(CandleInterval::from("1m"), CandleInterval::OneMinute);
+        assert_eq!(CandleInterval::from("1M"), CandleInterval::OneMonth);
+        assert_eq!(CandleInterval::from("1H"), CandleInterval::OneHour);
+        assert_eq!(TradeRole::Taker.to_string(), "TAKER");
+
+        let order: Order = serde_json::from_value(json!({
+            "id": "1234567890",
+            "sn": "OKMAKSDHRVVREK",
+            "market_symbol": "btcbrl",
+            "side": "SELL",
+            "type": "ICEBERG",
+            "state": "EXPIRED",
+            "price_avg": "0",
+            "quantity": "0.1",
+            "quantity_executed": "0",
+            "created_at": "2022-02-15T12:00:00.000Z",
+            "trades_count": 0
+        }))
+        .unwrap();
+        assert_eq!(order.side, Side::Sell);
+        assert_eq!(order.r#type, OrderType::Unknown("ICEBERG".into()));
+        assert_eq!(order.state, OrderState::Unknown("EXPIRED".into()));
+
+        let value = serde_json::to_value(&order).unwrap();
+        assert_eq!(value["type"], "ICEBERG");
+        assert_eq!(value["state"], "EXPIRED");
+    }
 
-        assert!(["BUY", "SELL"].contains(&order.side.as_str()), "Side should be either BUY or SELL");
-        assert!(["LIMIT", "MARKET"].contains(&order.r#type.as_str()), "Type should be either LIMIT or MARKET");
-        assert!(!order.state.is_empty(), "State should not be empty");
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_decimal_round_trip() {
+        use rust_foxbit_api::types::PriceLevel;
+        use rust_foxbit_api::Decimal;
+
+        let level: PriceLevel = serde_json::from_value(json!({
+            "price": "321654.123456789012345",
+            "volume": "0.10000000"
+        }))
+        .unwrap();
+        assert_eq!(level.price, "321654.123456789012345".parse::<Decimal>().unwrap());
+        assert_eq!(level.volume * Decimal::from(3), "0.30000000".parse::<Decimal>().unwrap());
+
+        let value = serde_json::to_value(&level).unwrap();
+        assert_eq!(value["price"], "321654.123456789012345");
+        assert_eq!(value["volume"], "0.10000000");
+    }
 
-        assert!(order.price.as_ref().map(|p| p.parse::<f64>().is_ok()).unwrap_or(true), "Price should be a valid number if present");
-        assert!(order.price_avg.parse::<f64>().is_ok(), "Price avg should be a valid number");
-        assert!(order.quantity.parse::<f64>().is_ok(), "Quantity should be a valid number");
-        assert!(order.quantity_executed.parse::<f64>().is_ok(), "Quantity executed should be a valid number");
+    #[test]
+    fn test_withdraw_info_compares_exactly() {
+        let withdraw_info: WithdrawInfo = serde_json::from_value(json!({
+            "enabled": true,
+            "min_amount": "0.0001",
+            "fee": "0.0005"
+        }))
+        .unwrap();
+
+        // Equal to the fee as an f64, but just above it.
+        assert!(withdraw_info.check_amount(&num("0.00050000000000000001")).is_ok());
+        assert!(withdraw_info.check_amount(&num("0.0005")).is_err());
+    }
 
-        assert!(order.instant_amount.as_ref().map(|p| p.parse::<f64>().is_ok()).unwrap_or(true), "Instant amount should be a valid number if present");
-        assert!(order.instant_amount_executed.as_ref().map(|p| p.parse::<f64>().is_ok()).unwrap_or(true), "Instant amount executed should be a valid number if present");
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_decimal_fee_is_exact() {
+        use rust_foxbit_api::Decimal;
+
+        let market: Market = serde_json::from_value(json!({
+            "symbol": "btcbrl",
+            "base": { "symbol": "btc", "precision": 8 },
+            "quote": { "symbol": "brl", "precision": 2 }
+        }))
+        .unwrap();
+        let trading_fee: TradingFee = serde_json::from_value(json!({
+            "market_symbol": "btcbrl",
+            "maker": "0.001",
+            "taker": "0.002"
+        }))
+        .unwrap();
+
+        // 0.1 * 0.2 is 0.020000000000000004 in floating point.
+        let sell = estimate_fee(&market, &trading_fee, Side::Sell, TradeRole::Maker, &num("0.1"), &num("0.2")).unwrap();
+        assert_eq!(sell.fee, "0.00002".parse::<Decimal>().unwrap());
+        assert_eq!(sell.net_amount, "0.01998".parse::<Decimal>().unwrap());
+    }
 
-        assert!(order.created_at.ends_with('Z'), "Created_at should end with 'Z' for UTC format");
-        assert!(order.trades_count >= 0, "Trades count should be a non-negative integer");
+    #[test]
+    fn test_normalize_order() {
+        let market: Market = serde_json::from_value(json!({
+            "symbol": "btcbrl",
+            "quantity_min": "0.0001",
+            "quantity_increment": "0.0001",
+            "price_min": "100",
+            "price_increment": "0.5",
+            "base": { "symbol": "btc", "precision": 8 },
+            "quote": { "symbol": "brl", "precision": 2 }
+        }))
+        .unwrap();
+        let order = OrderRequest::limit(Side::Buy, "btcbrl")
+            .quantity(num("0.12345"))
+            .price(num("300000.75"))
+            .build()
+            .unwrap();
+
+        let down = normalize_order(&order, &market, RoundingMode::Down).unwrap();
+        assert_eq!(down.quantity, Some(num("0.1234")));
+        assert_eq!(down.price, Some(num("300000.5")));
+
+        let up = normalize_order(&order, &market, RoundingMode::Up).unwrap();
+        assert_eq!(up.quantity, Some(num("0.1235")));
+        assert_eq!(up.price, Some(num("300001.0")));
+
+        let nearest = normalize_order(&order, &market, RoundingMode::Nearest).unwrap();
+        assert_eq!(nearest.quantity, Some(num("0.1235")));
+        assert_eq!(nearest.price, Some(num("300001.0")));
+
+        let strict = normalize_order(&order, &market, RoundingMode::Strict);
+        assert!(matches!(strict, Err(FoxbitError::Validation(_))));
+
+        // Aligned values are only reformatted to the increment's precision.
+        let aligned = OrderRequest::new(Side::Sell, OrderType::Market, "btcbrl", num("0.3"));
+        let aligned = normalize_order(&aligned, &market, RoundingMode::Strict).unwrap();
+        assert_eq!(aligned.quantity.unwrap().to_string(), "0.3000");
+
+        // Instant amounts follow the quote currency precision.
+        let instant = OrderRequest::instant(Side::Buy, "btcbrl")
+            .amount(num("1000.999"))
+            .build()
+            .unwrap();
+        let instant = normalize_order(&instant, &market, RoundingMode::Down).unwrap();
+        assert_eq!(instant.amount.unwrap().to_string(), "1000.99");
+
+        // Below the minimums once rounded
+        let dust = OrderRequest::new(Side::Sell, OrderType::Market, "btcbrl", num("0.00009"));
+        assert!(matches!(
+            normalize_order(&dust, &market, RoundingMode::Down),
+            Err(FoxbitError::Validation(_))
+        ));
+        let cheap = OrderRequest::limit(Side::Buy, "btcbrl")
+            .quantity(num("1"))
+            .price(num("99.5"))
+            .build()
+            .unwrap();
+        assert!(matches!(
+            normalize_order(&cheap, &market, RoundingMode::Down),
+            Err(FoxbitError::Validation(_))
+        ));
     }
 
     #[tokio::test]
-    async fn test_cancel_orders() {
+    async fn test_order_validation() {
         let mock_server = MockServer::start().await;
+        mount_btcbrl_market(&mock_server).await;
 
-        Mock::given(method("PUT"))
-            .and(path("/orders/cancel"))
+        Mock::given(method("POST"))
+            .and(path("/orders"))
+            .and(body_json(json!({
+                "side": "BUY",
+                "type": "MARKET",
+                "market_symbol": "btcbrl",
+                "quantity": "0.000001"
+            })))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .set_body_json(json!({
+                      "id": 1234567890,
+                      "sn": "OKMAKSDHRVVREK",
+                      "client_order_id": ""
+                    }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let order = OrderRequest::new(Side::Buy, OrderType::Market, "btcbrl", num("0.000001"));
+
+        // Below quantity_min, so rejected before it is sent.
+        let foxbit = Foxbit::new(Client::new(), mock_server.uri(), credentials());
+        let result = foxbit.submit_order(&order).await;
+        assert!(matches!(result, Err(FoxbitError::Validation(_))));
+
+        let unknown = OrderRequest::new(Side::Buy, OrderType::Market, "dogebrl", num("1"));
+        let result = foxbit.submit_order(&unknown).await;
+        assert!(matches!(result, Err(FoxbitError::Validation(_))));
+
+        // Sent as given once validation is turned off.
+        let foxbit = Foxbit::builder()
+            .api_url(mock_server.uri())
+            .credentials(credentials())
+            .skip_order_validation()
+            .build()
+            .unwrap();
+        let result = foxbit.submit_order(&order).await;
+        assert!(result.is_ok());
+    }
+
+    async fn mount_registry_lists(mock_server: &MockServer, expected_loads: impl Into<Times> + Clone) {
+        Mock::given(method("GET"))
+            .and(path("/markets"))
             .respond_with(
                 ResponseTemplate::new(200)
                     .set_body_json(json!({
                     "data": [
                         {
-                            "sn": "OKMAKSDHRVVREK",
-                            "id": 123456789
+                            "symbol": "btcbrl",
+                            "quantity_increment": "0.00001",
+                            "price_increment": "0.01",
+                            "base": { "symbol": "btc", "name": "Bitcoin", "type": "CRYPTO", "precision": 8, "category": { "code": "CRYPTO", "name": "Crypto" } },
+                            "quote": { "symbol": "brl", "name": "Real", "type": "FIAT", "precision": 2, "category": { "code": "FIAT", "name": "Fiat" } }
+                        },
+                        {
+                            "symbol": "usdtbrl",
+                            "base": { "symbol": "usdt", "name": "Tether", "type": "CRYPTO", "precision": 2, "category": { "code": "STABLECOIN", "name": "Stablecoin" } },
+                            "quote": { "symbol": "brl", "name": "Real", "type": "FIAT", "precision": 2, "category": { "code": "FIAT", "name": "Fiat" } }
                         }
                     ]
                 }))
                     .insert_header("content-type", "application/json"),
             )
-            .mount(&mock_server)
+            .expect(expected_loads.clone())
+            .mount(mock_server)
             .await;
 
-        let api_url = mock_server.uri();
-        let client: Client = Client::new();
-        let foxbit = Foxbit::new(client, api_url);
-
-        let result = foxbit.cancel_orders("ALL").await;
-        assert!(result.is_ok());
+        Mock::given(method("GET"))
+            .and(path("/currencies"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({
+                    "data": [
+                        { "symbol": "btc", "name": "Bitcoin", "type": "CRYPTO", "precision": 8, "category": { "code": "CRYPTO", "name": "Crypto" } },
+                        { "symbol": "usdt", "name": "Tether", "type": "CRYPTO", "precision": 2, "category": { "code": "STABLECOIN", "name": "Stablecoin" } },
+                        { "symbol": "brl", "name": "Real", "type": "FIAT", "precision": 2, "category": { "code": "FIAT", "name": "Fiat" } }
+                    ]
+                }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .expect(expected_loads)
+            .mount(mock_server)
+            .await;
+    }
 
-        let cancel_order_response = result.unwrap();
-        assert!(!cancel_order_response.is_empty(), "Cancel order response should not be empty");
+    #[tokio::test]
+    async fn test_market_registry_lookups() {
+        let mock_server = MockServer::start().await;
+        mount_registry_lists(&mock_server, 1).await;
+
+        let registry = MarketRegistry::new(Client::new(), mock_server.uri(), Duration::from_secs(60));
+        let snapshot = registry.snapshot().await.unwrap();
+        // Served from the cache.
+        let cached = registry.snapshot().await.unwrap();
+        assert_eq!(cached.loaded_at(), snapshot.loaded_at());
+
+        assert_eq!(snapshot.market("BTCBRL").unwrap().symbol.as_deref(), Some("btcbrl"));
+        assert_eq!(snapshot.market_for_pair("usdt", "BRL").unwrap().symbol.as_deref(), Some("usdtbrl"));
+        assert!(snapshot.market_for_pair("brl", "btc").is_none());
+        assert_eq!(snapshot.currency("brl").unwrap().precision, 2);
+        assert!(snapshot.require_currency("doge").is_err());
+
+        let stablecoins = snapshot.currencies_in_category("stablecoin");
+        assert_eq!(stablecoins.len(), 1);
+        assert_eq!(stablecoins[0].symbol.as_deref(), Some("usdt"));
+        let crypto_markets = snapshot.markets_in_category("CRYPTO");
+        assert_eq!(crypto_markets.len(), 1);
+        assert_eq!(crypto_markets[0].symbol.as_deref(), Some("btcbrl"));
+
+        let price = snapshot.round_price("btcbrl", &num("300000.129"), RoundingMode::Down).unwrap();
+        assert_eq!(price.to_string(), "300000.12");
+        let quantity = snapshot.round_quantity("btcbrl", &num("0.123456"), RoundingMode::Up).unwrap();
+        assert_eq!(quantity.to_string(), "0.12346");
+        // No quantity increment, so the base precision applies.
+        let quantity = snapshot.round_quantity("usdtbrl", &num("10.555"), RoundingMode::Nearest).unwrap();
+        assert_eq!(quantity.to_string(), "10.56");
+        assert!(snapshot.round_price("dogebrl", &num("1"), RoundingMode::Down).is_err());
+    }
 
-        for order in cancel_order_response {
-            assert!(!order.sn.is_empty(), "SN should not be empty");
-            assert!(order.id > 0, "Order ID should be a positive integer");
-        }
+    #[tokio::test]
+    async fn test_market_registry_background_refresh() {
+        let mock_server = MockServer::start().await;
+        // Polling after the refreshed lists are stale again may start another load.
+        mount_registry_lists(&mock_server, 2..).await;
+
+        let registry = MarketRegistry::new(Client::new(), mock_server.uri(), Duration::from_millis(50));
+        let first = registry.snapshot().await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        // Stale, so the cached lists are returned while a refresh starts.
+        let stale = registry.snapshot().await.unwrap();
+        assert_eq!(stale.loaded_at(), first.loaded_at());
+
+        let refreshed = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let snapshot = registry.snapshot().await.unwrap();
+                if snapshot.loaded_at() > first.loaded_at() {
+                    return snapshot;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Background refresh should replace the cached lists");
+        assert!(refreshed.market("btcbrl").is_some());
     }
 
     #[tokio::test]
-    async fn test_list_trades() {
+    async fn test_market_registry_shared_by_orders() {
         let mock_server = MockServer::start().await;
+        mount_registry_lists(&mock_server, 1).await;
 
-        Mock::given(method("GET"))
-            .and(path("/trades"))
+        Mock::given(method("POST"))
+            .and(path("/orders"))
             .respond_with(
-                ResponseTemplate::new(200)
+                ResponseTemplate::new(201)
                     .set_body_json(json!({
-                    "data": [{
-                        "id": 1234567890,
-                        "sn": "TC5JZVW2LLJ3IW",
-                        "order_id": "1234567890",
-                        "market_symbol": "btcbrl",
-                        "side": "BUY",
-                        "price": "290000.0",
-                        "quantity": "1.0",
-                        "fee": "0.01",
-                        "fee_currency_symbol": "btc",
-                        "created_at": "2021-02-15T22:06:32.999Z",
-                        "role": "TAKER",
-                    }]
-                }))
+                      "id": 1234567890,
+                      "sn": "OKMAKSDHRVVREK",
+                      "client_order_id": ""
+                    }))
                     .insert_header("content-type", "application/json"),
             )
+            .expect(2)
             .mount(&mock_server)
             .await;
 
-        let api_url = match env::var("API_ENV") {
-            Ok(env) if env == "production" => "https://api.foxbit.com.br/rest/v3".to_string(),
-            _ => mock_server.uri(),
-        };
+        let foxbit = Foxbit::builder()
+            .api_url(mock_server.uri())
+            .credentials(credentials())
+            .market_cache_ttl(Duration::from_secs(60))
+            .build()
+            .unwrap();
+        assert_eq!(foxbit.market_registry().ttl(), Duration::from_secs(60));
 
-        let client: Client = Client::new();
-        let foxbit = Foxbit::new(client, api_url);
+        let order = OrderRequest::new(Side::Buy, OrderType::Market, "btcbrl", num("0.5"));
+        foxbit.submit_order(&order).await.unwrap();
+        foxbit.submit_order(&order).await.unwrap();
 
-        let result = foxbit
-            .list_trades(
-                "2024-08-28T00:00:00.000Z", // start_time
-                "2024-08-29T20:00:22.013Z", // end_time
-                10,                         // page_size
-                1,                          // page
-                "btcbrl",                   // market_symbol
-            )
-            .await;
+        let snapshot = foxbit.market_registry().snapshot().await.unwrap();
+        assert!(snapshot.market("btcbrl").is_some());
+    }
 
-        assert!(result.is_ok());
+    fn order_json(id: u64) -> serde_json::Value {
+        json!({
+            "id": id.to_string(),
+            "sn": format!("SN{}", id),
+            "client_order_id": "",
+            "market_symbol": "btcbrl",
+            "side": "BUY",
+            "type": "LIMIT",
+            "state": "FILLED",
+            "price": "290000.0",
+            "price_avg": "290000.0",
+            "quantity": "0.42",
+            "quantity_executed": "0.42",
+            "instant_amount": "0.0",
+            "instant_amount_executed": "0.0",
+            "created_at": "2021-02-15T22:06:32.999Z",
+            "trades_count": 1,
+            "remark": "",
+            "funds_received": "0.42"
+        })
+    }
 
-        let listed_trades = result.unwrap();
-        assert!(!listed_trades.is_empty(), "Trades list should not be empty");
+    fn trade_json(id: u64) -> serde_json::Value {
+        json!({
+            "id": id,
+            "sn": format!("TC{}", id),
+            "order_id": "1234567890",
+            "market_symbol": "btcbrl",
+            "side": "BUY",
+            "price": "290000.0",
+            "quantity": "1.0",
+            "fee": "0.01",
+            "fee_currency_symbol": "btc",
+            "created_at": "2021-02-15T22:06:32.999Z",
+            "role": "TAKER"
+        })
+    }
 
-        for trade in listed_trades {
-            assert!(trade.id > 0, "Trade ID should be a positive integer");
-            assert!(!trade.sn.is_empty(), "SN should not be empty");
-            assert!(!trade.order_id.is_empty(), "Order ID should not be empty");
-            assert!(!trade.market_symbol.is_empty(), "Market symbol should not be empty");
-            assert!(["BUY", "SELL"].contains(&trade.side.as_str()), "Side should be either BUY or SELL");
+    async fn mount_page(mock_server: &MockServer, list_path: &str, page: &str, data: Vec<serde_json::Value>, expected: u64) {
+        Mock::given(method("GET"))
+            .and(path(list_path))
+            .and(query_param("page", page))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "data": data }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .expect(expected)
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_orders_stream() {
+        let mock_server = MockServer::start().await;
+        mount_page(&mock_server, "/orders", "1", vec![order_json(1), order_json(2)], 1).await;
+        mount_page(&mock_server, "/orders", "2", vec![order_json(3)], 1).await;
+        // The short second page ends the stream.
+        mount_page(&mock_server, "/orders", "3", vec![], 0).await;
+
+        let foxbit = Foxbit::new(Client::new(), mock_server.uri(), credentials());
+        let filter = OrderFilter {
+            market_symbol: Some("btcbrl".to_string()),
+            state: Some(OrderState::Filled),
+            page_size: Some(2),
+            ..Default::default()
+        };
+        let orders: Vec<_> = foxbit.orders_stream(&filter).collect().await;
+
+        let ids: Vec<String> = orders.into_iter().map(|order| order.unwrap().id).collect();
+        assert_eq!(ids, vec!["1", "2", "3"]);
+
+        let requests = mock_server.received_requests().await.unwrap();
+        let request = requests.iter().find(|request| request.url.path() == "/orders").unwrap();
+        let query = request.url.query().unwrap_or_default().to_string();
+        assert!(query.contains("market_symbol=btcbrl"));
+        assert!(query.contains("state=FILLED"));
+        assert!(query.contains("page_size=2"));
+        assert!(!query.contains("side="));
+    }
 
-            assert!(trade.price.parse::<f64>().is_ok(), "Price should be a valid number");
-            assert!(trade.quantity.parse::<f64>().is_ok(), "Quantity should be a valid number");
-            assert!(trade.fee.parse::<f64>().is_ok(), "Fee should be a valid number");
+    #[tokio::test]
+    async fn test_trades_stream_stops_after_error() {
+        let mock_server = MockServer::start().await;
+        mount_page(&mock_server, "/trades", "1", vec![trade_json(1), trade_json(2)], 1).await;
+        Mock::given(method("GET"))
+            .and(path("/trades"))
+            .and(query_param("page", "2"))
+            .respond_with(
+                ResponseTemplate::new(500)
+                    .set_body_json(json!({ "error": { "code": 500, "message": "Internal error" } }))
+                    .insert_header("content-type", "application/json"),
+            )
+            .mount(&mock_server)
+            .await;
+        mount_page(&mock_server, "/trades", "3", vec![trade_json(3), trade_json(4)], 1).await;
+
+        let foxbit = Foxbit::builder()
+            .api_url(mock_server.uri())
+            .credentials(credentials())
+            .page_budget(PageBudget { max_concurrent_pages: 3, min_interval: Duration::ZERO })
+            .build()
+            .unwrap();
+        let filter = TradeFilter { page_size: Some(2), ..Default::default() };
+        let trades: Vec<_> = foxbit.trades_stream(&filter).take(10).collect().await;
+
+        // Page 3 was fetched ahead, but nothing after the error is yielded.
+        assert_eq!(trades.len(), 3);
+        assert_eq!(trades[0].as_ref().unwrap().id, 1);
+        assert_eq!(trades[1].as_ref().unwrap().id, 2);
+        assert!(matches!(trades[2], Err(FoxbitError::Api(_))));
+    }
 
-            assert!(!trade.fee_currency_symbol.is_empty(), "Fee currency symbol should not be empty");
-            assert!(trade.created_at.ends_with('Z'), "Created_at should end with 'Z' for UTC format");
-            assert!(["TAKER", "MAKER"].contains(&trade.role.as_str()), "Role should be either TAKER or MAKER");
-        }
+    #[tokio::test]
+    async fn test_stream_page_budget() {
+        let mock_server = MockServer::start().await;
+        mount_page(&mock_server, "/trades", "2", vec![trade_json(1)], 1).await;
+        mount_page(&mock_server, "/trades", "3", vec![trade_json(2)], 1).await;
+        mount_page(&mock_server, "/trades", "4", vec![], 1).await;
+
+        let foxbit = Foxbit::builder()
+            .api_url(mock_server.uri())
+            .credentials(credentials())
+            .page_budget(PageBudget { max_concurrent_pages: 2, min_interval: Duration::from_millis(100) })
+            .build()
+            .unwrap();
+        let filter = TradeFilter { page_size: Some(1), page: Some(2), ..Default::default() };
+
+        let started = std::time::Instant::now();
+        let trades: Vec<_> = foxbit.trades_stream(&filter).collect().await;
+        assert_eq!(trades.len(), 2);
+        // Three page requests, started at least 100ms apart.
+        assert!(started.elapsed() >= Duration::from_millis(200));
     }
 }